@@ -1,308 +1,3784 @@
-#[derive(Clone, Debug)]
-enum Cell<T> {
+use alloc::collections::TryReserveError;
+use alloc::vec::Vec;
+
+#[cfg(feature = "serde")]
+mod serde;
+#[cfg(feature = "rayon")]
+mod rayon;
+#[cfg(feature = "rayon")]
+pub use self::rayon::{ParIter, ParIterMut};
+
+/// Marks the end of the list of empty elements.
+const NO_HOLE: usize = usize::MAX;
+
+/// Number of elements per word of the occupancy bitset.
+const WORD_BITS: usize = u64::BITS as usize;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Type of indexes kept in empty elements of [HoleyVec] to link them into the list. A smaller
+/// type reduces the memory used by empty elements but limits [HoleyVec::index_upper_bound] by
+/// the maximal value of the type. The trait is implemented for `u16`, `u32` and `usize`.
+///
+/// ## Examples
+/// ```
+/// use holeyvec::HoleyVec;
+/// use std::panic::{catch_unwind, AssertUnwindSafe};
+///
+/// let mut v: HoleyVec<String, u32> = HoleyVec::default();
+/// v.push("a".to_string());
+/// v.push("b".to_string());
+/// v.remove(0);
+/// assert_eq!(v.push("c".to_string()), 0);
+/// assert_eq!(v.iter().cloned().collect::<Vec<String>>(), vec!["c", "b"]);
+///
+/// // Index upper bound is limited by the maximal value of the type
+/// let mut v: HoleyVec<(), u16> = std::iter::repeat(()).take(u16::MAX as usize).collect();
+/// assert_eq!(v.index_upper_bound(), u16::MAX as usize);
+/// assert!(catch_unwind(AssertUnwindSafe(|| v.push(()))).is_err());
+/// v.remove(42);
+/// assert_eq!(v.push(()), 42);
+///
+/// let mut v: HoleyVec<(), u32> = HoleyVec::default();
+/// assert!(catch_unwind(AssertUnwindSafe(|| v.insert(u32::MAX as usize, ()))).is_err());
+/// assert_eq!(v.index_upper_bound(), 0);
+/// ```
+pub trait Index: sealed::Sealed + Copy + Eq + core::fmt::Debug {
+    /// Maximal value of the type. It is reserved to mark the end of the list of empty elements.
+    const MAX: Self;
+
+    /// Convert index from `usize`, index is expected to be less than [Index::MAX].
+    fn from_usize(index: usize) -> Self;
+
+    /// Convert index into `usize`.
+    fn into_usize(self) -> usize;
+}
+
+macro_rules! impl_index {
+    ($($type:ty),*) => {
+        $(
+            impl sealed::Sealed for $type {}
+
+            impl Index for $type {
+                const MAX: Self = <$type>::MAX;
+
+                fn from_usize(index: usize) -> Self {
+                    index as $type
+                }
+
+                fn into_usize(self) -> usize {
+                    self as usize
+                }
+            }
+        )*
+    };
+}
+
+impl_index!(u16, u32, usize);
+
+/// Convert index of the next hole into a link kept in the empty element.
+fn to_link<Idx: Index>(index: usize) -> Idx {
+    if index == NO_HOLE {
+        Idx::MAX
+    } else {
+        Idx::from_usize(index)
+    }
+}
+
+/// Convert link kept in the empty element into an index of the next hole.
+fn from_link<Idx: Index>(link: Idx) -> usize {
+    if link == Idx::MAX {
+        NO_HOLE
+    } else {
+        link.into_usize()
+    }
+}
+
+#[derive(Clone)]
+enum Cell<T, Idx> {
     Value(T),
-    Hole(usize),
+    Hole(Idx),
 }
 
-#[derive(Clone, Debug)]
-/// Vector with holes implementation.
-pub struct HoleyVec<T> {
+#[derive(Clone)]
+/// Vector with holes implementation. Type of the indexes kept in empty elements can be changed
+/// using `Idx` parameter, see [Index].
+pub struct HoleyVec<T, Idx = usize> {
     first_hole: usize,
-    vec: Vec<Cell<T>>,
+    len: usize,
+    vec: Vec<Cell<T, Idx>>,
+    /// Generations of elements, see [Key]. Generations are tracked only up to the greatest
+    /// index a key was issued for, thus vectors which don't use keys don't pay for them.
+    /// Missing generations are zero.
+    generations: Vec<u32>,
+    /// Maximal index of the element, see [HoleyVec::with_max_index].
+    max_index: usize,
+    /// Occupancy bitset, bit is set when element is not empty. Bits beyond the end of the
+    /// vector are zero. The bitset covers the whole vector, i.e. it has at least
+    /// `vec.len().div_ceil(WORD_BITS)` words, iterators and searches through the bitset rely
+    /// on it.
+    occupied: Vec<u64>,
+    /// Order of reusing holes, see [Reuse].
+    reuse: Reuse,
+}
+
+/// Order in which [HoleyVec::push] reuses holes, see [HoleyVec::with_reuse].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Reuse {
+    /// The last removed element is reused first. Holes added by [HoleyVec::insert] out of
+    /// bounds are reused before others in ascending order. Removing takes constant time.
+    #[default]
+    LastRemoved,
+    /// The element with the lowest index is reused first, thus values are kept close to the
+    /// beginning of the vector and the order doesn't depend on the history of removals.
+    /// Removing requires searching the previous hole in the occupancy bitset, thus it takes time
+    /// proportional to the distance to the previous hole.
+    LowestIndex,
+}
+
+/// Key of the value in [HoleyVec] which detects reuse of the element. In addition to the index
+/// it keeps a generation of the element. The generation is changed each time a value is removed
+/// from the element, thus a key of the removed value doesn't give an access to the value which
+/// was put in its place later. Generation is a 32 bit counter, it wraps around after 2^32
+/// removals from the same element.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Key {
+    index: usize,
+    generation: u32,
+}
+
+impl Key {
+    /// Return index of the value in [HoleyVec].
+    pub fn index(&self) -> usize {
+        self.index
+    }
 }
 
 impl<T> HoleyVec<T> {
 
-    /// Initialize a new, empty vector.
-    pub fn new() -> Self {
-        Self{ first_hole: 0, vec: Vec::new() }
+    /// Initialize a new, empty vector. Can be used in constant context.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    /// use std::sync::Mutex;
+    ///
+    /// static REGISTRY: Mutex<HoleyVec<&str>> = Mutex::new(HoleyVec::new());
+    ///
+    /// let index = REGISTRY.lock().unwrap().push("entry");
+    /// assert_eq!(REGISTRY.lock().unwrap().get(index), Some(&"entry"));
+    /// ```
+    pub const fn new() -> Self {
+        Self::empty()
     }
 
-    /// Return next index of the empty element. This index is used by [HoleyVec::push] method to
-    /// put a passed value.
+    /// Initialize a new, empty vector with at least the specified capacity. Similar to
+    /// [std::vec::Vec::with_capacity]. See [HoleyVec::with_params] for other index types.
     ///
     /// ## Examples
     /// ```
     /// use holeyvec::HoleyVec;
     ///
-    /// let mut v = HoleyVec::new();
+    /// let mut v = HoleyVec::with_capacity(10);
+    /// let capacity = v.capacity();
+    /// assert!(capacity >= 10);
     /// assert_eq!(v.next_index(), 0);
+    /// assert_eq!(v.index_upper_bound(), 0);
     ///
-    /// v.push(42);
-    /// assert_eq!(v.next_index(), 1);
+    /// for i in 0..capacity {
+    ///     v.push(i);
+    /// }
+    /// assert_eq!(v.capacity(), capacity);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self{ vec: Vec::with_capacity(capacity), ..Self::new() }
+    }
+
+    /// Initialize a new, empty vector which cannot have elements with indexes greater than
+    /// `max_index`. [HoleyVec::try_push] returns the value back when there are no holes and
+    /// the maximal index is reached, [HoleyVec::push] and [HoleyVec::insert] panic. See
+    /// [HoleyVec::with_params] for other index types.
     ///
-    /// v.remove(0);
-    /// assert_eq!(v.next_index(), 0);
+    /// ## Examples
     /// ```
-    pub fn next_index(&self) -> usize {
-        self.first_hole
+    /// use holeyvec::HoleyVec;
+    /// use std::panic::{catch_unwind, AssertUnwindSafe};
+    ///
+    /// let mut v = HoleyVec::with_max_index(2);
+    /// assert_eq!(v.try_push('a'), Ok(0));
+    /// assert_eq!(v.try_push('b'), Ok(1));
+    /// assert_eq!(v.try_push('c'), Ok(2));
+    /// assert_eq!(v.try_push('d'), Err('d'));
+    /// assert_eq!(v.index_upper_bound(), 3);
+    ///
+    /// v.remove(1);
+    /// assert_eq!(v.try_push('d'), Ok(1));
+    /// assert_eq!(v.try_push('e'), Err('e'));
+    ///
+    /// let error = catch_unwind(AssertUnwindSafe(|| v.push('e'))).unwrap_err();
+    /// assert_eq!(*error.downcast::<String>().unwrap(), "Index 3 exceeds the maximal index 2");
+    /// assert!(catch_unwind(AssertUnwindSafe(|| v.insert(5, 'f'))).is_err());
+    /// assert_eq!(v.len(), 3);
+    /// ```
+    pub fn with_max_index(max_index: usize) -> Self {
+        Self{ max_index, ..Self::new() }
     }
 
-    /// Return number of indexes used. This number includes both empty and non-empty elements.
-    /// 
+    /// Initialize a new, empty vector which reuses holes in the specified order, see [Reuse].
+    /// See [HoleyVec::with_params] for other index types.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::{HoleyVec, Reuse};
+    ///
+    /// let mut lowest: HoleyVec<i32> = HoleyVec::with_reuse(Reuse::LowestIndex);
+    /// let mut last: HoleyVec<i32> = HoleyVec::new();
+    /// assert_eq!(last.reuse(), Reuse::LastRemoved);
+    /// for v in [&mut lowest, &mut last] {
+    ///     v.extend(0..8);
+    ///     v.remove(5);
+    ///     v.remove(2);
+    ///     v.remove(6);
+    ///     v.insert(10, 10);
+    /// }
+    ///
+    /// assert_eq!(lowest.next_indices(7), vec![2, 5, 6, 8, 9, 11, 12]);
+    /// assert_eq!(last.next_indices(7), vec![8, 9, 6, 2, 5, 11, 12]);
+    /// assert_eq!(lowest.push(20), 2);
+    /// assert_eq!(lowest.push(21), 5);
+    /// assert_eq!(last.push(20), 8);
+    /// assert_eq!(last.push(21), 9);
+    ///
+    /// // Removing in any order keeps holes sorted
+    /// lowest.remove(7);
+    /// lowest.remove(0);
+    /// lowest.remove(4);
+    /// assert_eq!(lowest.holes().collect::<Vec<usize>>(), vec![0, 4, 6, 7, 8, 9]);
+    /// lowest.insert(7, 70);
+    /// lowest.insert(0, 0);
+    /// lowest.truncate(9);
+    /// assert_eq!(lowest.holes().collect::<Vec<usize>>(), vec![4, 6, 8]);
+    /// assert_eq!((0..4).map(|value| lowest.push(value)).collect::<Vec<usize>>(),
+    ///     vec![4, 6, 8, 9]);
+    ///
+    /// // Mode is kept by split_off()
+    /// let mut right = lowest.split_off(3);
+    /// right.remove(5);
+    /// right.remove(1);
+    /// assert_eq!(right.reuse(), Reuse::LowestIndex);
+    /// assert_eq!(right.push(0), 1);
+    /// assert_eq!(right.push(1), 5);
+    /// ```
+    pub fn with_reuse(reuse: Reuse) -> Self {
+        Self{ reuse, ..Self::new() }
+    }
+
+    /// Initialize a new vector of `n` elements without holes. Each element is returned by the
+    /// function `f` called with the index of the element in ascending order.
+    ///
     /// ## Examples
     /// ```
     /// use holeyvec::HoleyVec;
     ///
-    /// let mut v = HoleyVec::new();
-    /// assert_eq!(v.index_upper_bound(), 0);
+    /// let v = HoleyVec::from_fn(4, |i| i * 10);
+    /// assert_eq!(v.len(), 4);
+    /// assert_eq!(v.hole_count(), 0);
+    /// assert_eq!(v.next_index(), 4);
+    /// assert_eq!(v.iter().copied().collect::<Vec<usize>>(), vec![0, 10, 20, 30]);
+    /// ```
     ///
-    /// v.push(42);
-    /// assert_eq!(v.index_upper_bound(), 1);
+    /// Values which are already constructed are dropped when the function panics:
+    /// ```
+    /// use holeyvec::HoleyVec;
+    /// use std::rc::Rc;
     ///
-    /// v.remove(0);
-    /// assert_eq!(v.index_upper_bound(), 1);
+    /// let value = Rc::new(42);
+    /// let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    ///     HoleyVec::from_fn(4, |i| if i < 2 { value.clone() } else { panic!() })
+    /// }));
+    /// assert!(result.is_err());
+    /// assert_eq!(Rc::strong_count(&value), 1);
     /// ```
-    pub fn index_upper_bound(&self) -> usize {
-        self.vec.len()
+    pub fn from_fn<F: FnMut(usize) -> T>(n: usize, f: F) -> Self {
+        (0..n).map(f).collect()
     }
 
-    /// Return underlying vector's capacity. Similar to [std::vec::Vec::capacity].
+    /// Initialize a new vector putting each value at the index it is paired with. Values may
+    /// come in any order, when the same index is used several times the last value wins.
+    /// Indexes which are not mentioned become holes which are reused by [HoleyVec::push] in
+    /// ascending order.
     ///
     /// ## Examples
     /// ```
     /// use holeyvec::HoleyVec;
     ///
-    /// let mut v = HoleyVec::new();
-    /// assert_eq!(v.capacity(), 0);
+    /// let mut v = HoleyVec::from_entries([(3, 'd'), (1, 'b'), (3, 'e')]);
+    /// assert_eq!(v.len(), 2);
+    /// assert_eq!(v.index_upper_bound(), 4);
+    /// assert_eq!(v.get(1), Some(&'b'));
+    /// assert_eq!(v.get(3), Some(&'e'));
+    /// assert_eq!(v.next_index(), 0);
+    /// assert!(v.is_hole(v.next_index()));
     ///
-    /// v.push(42);
-    /// assert!(v.capacity() >= 1);
+    /// assert_eq!(v.push('a'), 0);
+    /// assert_eq!(v.push('c'), 2);
+    /// assert_eq!(v.push('f'), 4);
     /// ```
-    pub fn capacity(&self) -> usize {
-        self.vec.capacity()
+    pub fn from_entries<I: IntoIterator<Item=(usize, T)>>(entries: I) -> Self {
+        Self::collect_entries(entries)
     }
+}
 
-    /// Check if element by index is empty.
-    /// 
+impl<T, Idx: Index> HoleyVec<T, Idx> {
+
+    /// Initialize a new, empty vector with any index type (see [Index]). Capacity, maximal
+    /// index and order of reusing holes are set as [HoleyVec::with_capacity],
+    /// [HoleyVec::with_max_index] and [HoleyVec::with_reuse] do. Pass `usize::MAX` as
+    /// `max_index` to limit indexes by the index type only.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::{HoleyVec, Reuse};
+    ///
+    /// let mut v: HoleyVec<String, u32> = HoleyVec::with_params(4, 2, Reuse::LowestIndex);
+    /// assert!(v.capacity() >= 4);
+    /// assert_eq!(v.reuse(), Reuse::LowestIndex);
+    /// for s in ["a", "b", "c"] {
+    ///     assert!(v.try_push(s.to_string()).is_ok());
+    /// }
+    /// assert_eq!(v.try_push("d".to_string()), Err("d".to_string()));
+    /// v.remove(2);
+    /// v.remove(0);
+    /// assert_eq!(v.push("e".to_string()), 0);
+    /// ```
+    pub fn with_params(capacity: usize, max_index: usize, reuse: Reuse) -> Self {
+        Self{ vec: Vec::with_capacity(capacity), max_index, reuse, ..Self::default() }
+    }
+
+    /// Empty vector with default parameters, shared by [HoleyVec::new] and [Default].
+    const fn empty() -> Self {
+        Self{ first_hole: NO_HOLE, len: 0, vec: Vec::new(), generations: Vec::new(),
+            max_index: usize::MAX, occupied: Vec::new(), reuse: Reuse::LastRemoved }
+    }
+
+    /// Return next index of the empty element. This index is used by [HoleyVec::push] method to
+    /// put a passed value.
+    ///
     /// ## Examples
     /// ```
     /// use holeyvec::HoleyVec;
     ///
     /// let mut v = HoleyVec::new();
-    /// // No holes in empty vector
-    /// assert!(!v.is_hole(0));
+    /// assert_eq!(v.next_index(), 0);
     ///
     /// v.push(42);
-    /// assert!(!v.is_hole(0));
+    /// assert_eq!(v.next_index(), 1);
     ///
     /// v.remove(0);
-    /// assert!(v.is_hole(0));
+    /// assert_eq!(v.next_index(), 0);
     /// ```
-    pub fn is_hole(&self, index: usize) -> bool {
-        match self.vec.get(index) {
-            Some(Cell::Hole(_)) => true,
-            _ => false,
+    pub fn next_index(&self) -> usize {
+        if self.first_hole == NO_HOLE {
+            self.vec.len()
+        } else {
+            self.first_hole
         }
     }
 
-    /// Get value by index.
-    /// 
+    /// Return indexes which are used by the next `n` calls of [HoleyVec::push]. Holes are
+    /// returned first, then indexes after [HoleyVec::index_upper_bound] follow.
+    ///
     /// ## Examples
     /// ```
     /// use holeyvec::HoleyVec;
     ///
-    /// let mut v = HoleyVec::new();
-    /// assert_eq!(v.get(0), None);
+    /// let mut v: HoleyVec<i32> = (0..5).collect();
+    /// v.remove(1);
+    /// v.remove(3);
+    /// assert_eq!(v.next_indices(4), vec![3, 1, 5, 6]);
+    /// assert_eq!(v.next_indices(1), vec![v.next_index()]);
+    /// assert!(v.next_indices(0).is_empty());
+    /// ```
+    pub fn next_indices(&self, n: usize) -> Vec<usize> {
+        self.holes().chain(self.vec.len()..).take(n).collect()
+    }
+
+    /// Return the order in which holes are reused, see [Reuse].
+    pub fn reuse(&self) -> Reuse {
+        self.reuse
+    }
+
+    /// Change the order in which holes are reused, see [Reuse]. Switching to
+    /// [Reuse::LowestIndex] sorts the existing holes, it takes time proportional to
+    /// [HoleyVec::index_upper_bound].
     ///
-    /// v.push(42);
-    /// assert_eq!(v.get(0), Some(&42));
+    /// ## Examples
+    /// ```
+    /// use holeyvec::{HoleyVec, Reuse};
+    ///
+    /// let mut v: HoleyVec<i32> = (0..6).collect();
+    /// v.remove(4);
+    /// v.remove(1);
+    /// v.remove(3);
+    /// assert_eq!(v.holes().collect::<Vec<usize>>(), vec![3, 1, 4]);
+    ///
+    /// v.set_reuse(Reuse::LowestIndex);
+    /// assert_eq!(v.holes().collect::<Vec<usize>>(), vec![1, 3, 4]);
+    /// v.remove(2);
+    /// assert_eq!(v.push(10), 1);
+    /// assert_eq!(v.push(20), 2);
     ///
+    /// v.set_reuse(Reuse::LastRemoved);
     /// v.remove(0);
-    /// assert_eq!(v.get(0), None);
+    /// assert_eq!(v.push(30), 0);
+    /// assert_eq!(v.push(40), 3);
     /// ```
-    pub fn get(&self, index: usize) -> Option<&T> {
-        match self.vec.get(index) {
-            Some(Cell::Value(value)) => Some(value),
-            _ => None,
+    pub fn set_reuse(&mut self, reuse: Reuse) {
+        if reuse == Reuse::LowestIndex && self.reuse != reuse {
+            self.link_holes();
         }
+        self.reuse = reuse;
     }
 
-    /// Get mutable value by index.
+    /// Return number of indexes used. This number includes both empty and non-empty elements.
     /// 
     /// ## Examples
     /// ```
     /// use holeyvec::HoleyVec;
     ///
     /// let mut v = HoleyVec::new();
-    /// assert_eq!(v.get_mut(0), None);
+    /// assert_eq!(v.index_upper_bound(), 0);
     ///
     /// v.push(42);
-    /// v.get_mut(0).map(|v| *v = 24);
-    /// assert_eq!(v.get(0), Some(&24));
+    /// assert_eq!(v.index_upper_bound(), 1);
     ///
     /// v.remove(0);
-    /// assert_eq!(v.get_mut(0), None);
+    /// assert_eq!(v.index_upper_bound(), 1);
     /// ```
-    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
-        match self.vec.get_mut(index) {
-            Some(Cell::Value(value)) => Some(value),
-            _ => None,
-        }
+    pub fn index_upper_bound(&self) -> usize {
+        self.vec.len()
     }
 
-    /// Push value to vector using the index of the first empty element (see
-    /// [HoleyVec::next_index]).
-    /// 
+    /// Return number of non-empty elements.
+    ///
     /// ## Examples
     /// ```
     /// use holeyvec::HoleyVec;
     ///
     /// let mut v = HoleyVec::new();
+    /// assert_eq!(v.len(), 0);
     ///
-    /// v.push(42);
-    /// assert_eq!(v.get(0), Some(&42));
+    /// v.push(1);
+    /// v.push(2);
+    /// v.push(3);
+    /// assert_eq!(v.len(), 3);
     ///
-    /// v.push(43);
-    /// assert_eq!(v.get(0), Some(&42));
-    /// assert_eq!(v.get(1), Some(&43));
+    /// v.remove(1);
+    /// assert_eq!(v.len(), 2);
+    /// assert_eq!(v.index_upper_bound(), 3);
     ///
-    /// v.remove(0);
-    /// assert_eq!(v.get(0), None);
-    /// assert_eq!(v.get(1), Some(&43));
+    /// v.push(4);
+    /// assert_eq!(v.len(), 3);
     ///
-    /// v.push(24);
-    /// assert_eq!(v.get(0), Some(&24));
-    /// assert_eq!(v.get(1), Some(&43));
+    /// v.remove(2);
+    /// assert_eq!(v.len(), 2);
+    ///
+    /// // Failed removal doesn't change the number of elements
+    /// let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| v.remove(2)));
+    /// assert!(result.is_err());
+    /// assert_eq!(v.len(), 2);
+    /// let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| v.remove(3)));
+    /// assert!(result.is_err());
+    /// assert_eq!(v.len(), 2);
+    ///
+    /// v.remove(0);
+    /// v.remove(1);
+    /// assert_eq!(v.len(), 0);
+    /// assert!(v.is_empty());
     /// ```
-    pub fn push(&mut self, value: T) -> usize {
-        if self.first_hole >= self.vec.len() {
-            let index = self.vec.len();
-            self.vec.push(Cell::Value(value));
-            self.first_hole = index + 1;
-            index
-        } else {
-            let index = self.first_hole;
-            match self.vec[index] {
-                Cell::Hole(next_hole) => {
-                    self.first_hole = next_hole;
-                    self.vec[index] = Cell::Value(value);
-                },
-                _ => panic!("Unexpected state"),
-            }
-            index
-        }
+    pub fn len(&self) -> usize {
+        self.len
     }
 
-    /// Remove value by index.
-    /// 
+    /// Check if vector has no non-empty elements. Vector which contains holes only is empty.
+    ///
     /// ## Examples
     /// ```
     /// use holeyvec::HoleyVec;
     ///
     /// let mut v = HoleyVec::new();
+    /// assert!(v.is_empty());
     ///
     /// v.push(42);
-    /// v.push(24);
-    /// assert_eq!(v.get(0), Some(&42));
-    /// assert_eq!(v.get(1), Some(&24));
+    /// assert!(!v.is_empty());
     ///
     /// v.remove(0);
-    /// assert_eq!(v.get(0), None);
-    /// assert_eq!(v.get(1), Some(&24));
+    /// assert!(v.is_empty());
+    /// assert_eq!(v.index_upper_bound(), 1);
     /// ```
-    pub fn remove(&mut self, index: usize) -> T {
-        let mut value = Cell::Hole(self.first_hole);
-        std::mem::swap(&mut self.vec[index], &mut value);
-        match value {
-            Cell::Value(value) => {
-                self.first_hole = index;
-                value
-            },
-            Cell::Hole(_) => {
-                panic!("Index doesn't exist");
-            }
-        }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
     }
 
-    /// Return iterator over non empty elements of the vector.
-    /// 
+    /// Return number of empty elements. Together with [HoleyVec::len] it gives
+    /// [HoleyVec::index_upper_bound].
+    ///
     /// ## Examples
     /// ```
     /// use holeyvec::HoleyVec;
     ///
     /// let mut v = HoleyVec::new();
-    /// let mut it = v.iter();
-    /// assert_eq!(it.next(), None);
+    /// assert_eq!(v.hole_count(), 0);
     ///
     /// v.push(1);
     /// v.push(2);
     /// v.push(3);
+    /// assert_eq!(v.hole_count(), 0);
+    ///
+    /// v.remove(0);
     /// v.remove(1);
-    /// let mut it = v.iter();
-    /// assert_eq!(it.next(), Some(&1));
-    /// assert_eq!(it.next(), Some(&3));
+    /// assert_eq!(v.hole_count(), 2);
+    ///
+    /// v.push(4);
+    /// assert_eq!(v.hole_count(), 1);
+    /// assert_eq!(v.len() + v.hole_count(), v.index_upper_bound());
     /// ```
-    pub fn iter(&self) -> Iter<T> {
-        Iter::new(self)
+    pub fn hole_count(&self) -> usize {
+        self.vec.len() - self.len
     }
 
-    /// Return mutable iterator over non empty elements of the vector.
-    /// 
+    /// Return the share of empty elements among all used indexes, a number from 0.0 to 1.0. It
+    /// is 0.0 for the vector without indexes used.
+    ///
     /// ## Examples
     /// ```
     /// use holeyvec::HoleyVec;
     ///
     /// let mut v = HoleyVec::new();
-    /// let mut it = v.iter_mut();
-    /// assert_eq!(it.next(), None);
+    /// assert_eq!(v.fragmentation(), 0.0);
     ///
     /// v.push(1);
     /// v.push(2);
     /// v.push(3);
+    /// v.push(4);
+    /// assert_eq!(v.fragmentation(), 0.0);
+    ///
+    /// v.remove(0);
+    /// v.remove(2);
+    /// assert_eq!(v.fragmentation(), 0.5);
+    ///
     /// v.remove(1);
-    /// let mut it = v.iter_mut();
-    /// assert_eq!(it.next(), Some(&mut 1));
-    /// assert_eq!(it.next(), Some(&mut 3));
+    /// v.remove(3);
+    /// assert_eq!(v.fragmentation(), 1.0);
     /// ```
-    pub fn iter_mut(&mut self) -> IterMut<T> {
-        IterMut::new(self)
-    }
-}
-
-impl<T> std::ops::Index<usize> for HoleyVec<T> {
-    type Output = T;
-
-    fn index(&self, index: usize) -> &Self::Output {
-        self.get(index).expect("Index doesn't exist")
+    pub fn fragmentation(&self) -> f64 {
+        if self.vec.is_empty() {
+            0.0
+        } else {
+            self.hole_count() as f64 / self.vec.len() as f64
+        }
     }
-}
 
-impl<T> std::ops::IndexMut<usize> for HoleyVec<T> {
-
-    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
-        self.get_mut(index).expect("Index doesn't exist")
+    /// Return underlying vector's capacity. Similar to [std::vec::Vec::capacity].
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v = HoleyVec::new();
+    /// assert_eq!(v.capacity(), 0);
+    ///
+    /// v.push(42);
+    /// assert!(v.capacity() >= 1);
+    /// ```
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
     }
-}
-
-/// [HoleyVec] iterator to iterate through non-empty elements of the vector.
-pub struct Iter<'a, T> {
-    delegate: std::slice::Iter<'a, Cell<T>>
-}
 
-impl<'a, T> Iter<'a, T> {
-    fn new(vec: &'a HoleyVec<T>) -> Self {
-        Self{ delegate: vec.vec.iter() }
+    /// Reserve capacity for at least `additional` more pushes. Empty elements are reused by
+    /// [HoleyVec::push] first, thus only pushes which don't fit into the holes require
+    /// capacity. Similar to [std::vec::Vec::reserve].
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v = HoleyVec::<i32>::new();
+    /// v.reserve(10);
+    /// assert!(v.capacity() >= 10);
+    ///
+    /// let mut v: HoleyVec<i32> = (0..4).collect();
+    /// v.remove(0);
+    /// v.remove(2);
+    ///
+    /// // All pushes fall into holes
+    /// v.reserve(2);
+    /// assert!(v.capacity() >= 4);
+    ///
+    /// // Some pushes fall into holes
+    /// v.reserve(10);
+    /// assert!(v.capacity() >= 12);
+    ///
+    /// // No holes left
+    /// v.push(4);
+    /// v.push(5);
+    /// v.reserve(20);
+    /// assert!(v.capacity() >= 24);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        self.vec.reserve(additional.saturating_sub(self.hole_count()));
+    }
+
+    /// Reserve minimal capacity for at least `additional` more pushes. Similar to
+    /// [HoleyVec::reserve] but doesn't overallocate (see [std::vec::Vec::reserve_exact]).
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v = HoleyVec::<i32>::new();
+    /// v.reserve_exact(10);
+    /// assert!(v.capacity() >= 10);
+    ///
+    /// let mut v: HoleyVec<i32> = (0..4).collect();
+    /// v.shrink_to_fit();
+    /// v.remove(0);
+    /// v.remove(2);
+    ///
+    /// // All pushes fall into holes
+    /// v.reserve_exact(2);
+    /// assert_eq!(v.capacity(), 4);
+    ///
+    /// // Some pushes fall into holes
+    /// v.reserve_exact(10);
+    /// assert!(v.capacity() >= 12);
+    /// ```
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.vec.reserve_exact(additional.saturating_sub(self.hole_count()));
+    }
+
+    /// Try to reserve capacity for at least `additional` more pushes. Same as
+    /// [HoleyVec::reserve] but returns an error instead of panicking or aborting when
+    /// allocation fails (see [std::vec::Vec::try_reserve]).
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..4).collect();
+    /// v.remove(0);
+    /// v.remove(2);
+    /// assert!(v.try_reserve(10).is_ok());
+    /// assert!(v.capacity() >= 12);
+    ///
+    /// assert!(v.try_reserve(usize::MAX).is_err());
+    /// ```
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.vec.try_reserve(additional.saturating_sub(self.hole_count()))
+    }
+
+    /// Try to reserve minimal capacity for at least `additional` more pushes. Same as
+    /// [HoleyVec::reserve_exact] but returns an error instead of panicking or aborting when
+    /// allocation fails (see [std::vec::Vec::try_reserve_exact]).
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..4).collect();
+    /// v.shrink_to_fit();
+    /// v.remove(0);
+    /// v.remove(2);
+    /// assert!(v.try_reserve_exact(2).is_ok());
+    /// assert_eq!(v.capacity(), 4);
+    /// assert!(v.try_reserve_exact(10).is_ok());
+    /// assert!(v.capacity() >= 12);
+    ///
+    /// assert!(v.try_reserve_exact(usize::MAX).is_err());
+    /// ```
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), TryReserveError> {
+        self.vec.try_reserve_exact(additional.saturating_sub(self.hole_count()))
+    }
+
+    /// Extend the vector by holes up to `up_to` index upper bound. Does nothing when
+    /// [HoleyVec::index_upper_bound] is already greater or equal to `up_to`. New holes are
+    /// reused by [HoleyVec::push] before existing holes in ascending order. Filling them by
+    /// [HoleyVec::insert] doesn't grow the underlying vector. Panics when `up_to - 1` exceeds the
+    /// maximal index (see [HoleyVec::with_max_index] and [Index]).
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = HoleyVec::new();
+    /// v.reserve_holes(10);
+    /// assert_eq!(v.index_upper_bound(), 10);
+    /// assert_eq!(v.hole_count(), 10);
+    /// assert!(v.capacity() >= 10);
+    ///
+    /// let capacity = v.capacity();
+    /// assert_eq!(v.insert(7, 7), None);
+    /// assert_eq!(v.capacity(), capacity);
+    /// assert_eq!((0..9).map(|value| v.push(value)).collect::<Vec<usize>>(),
+    ///     vec![0, 1, 2, 3, 4, 5, 6, 8, 9]);
+    /// assert_eq!(v.push(10), 10);
+    ///
+    /// // Existing holes are reused after the new ones
+    /// let mut v: HoleyVec<i32> = (0..3).collect();
+    /// v.remove(1);
+    /// v.reserve_holes(5);
+    /// v.reserve_holes(2);
+    /// assert_eq!(v.hole_count(), 3);
+    /// assert_eq!((0..4).map(|value| v.push(value)).collect::<Vec<usize>>(), vec![3, 4, 1, 5]);
+    /// ```
+    #[track_caller]
+    pub fn reserve_holes(&mut self, up_to: usize) {
+        if up_to > self.vec.len() {
+            self.check_new_index(up_to - 1);
+            self.grow_with_holes(up_to);
+        }
+    }
+
+    /// Remove empty elements from the end of the vector and shrink the capacity of the
+    /// underlying vector as much as possible. Similar to [std::vec::Vec::shrink_to_fit].
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..8).collect();
+    /// v.remove(1);
+    /// v.remove(6);
+    /// v.remove(3);
+    /// v.remove(7);
+    /// v.remove(2);
+    /// assert_eq!(v.next_index(), 2);
+    ///
+    /// v.shrink_to_fit();
+    /// assert_eq!(v.index_upper_bound(), 6);
+    /// assert!(v.capacity() >= 6);
+    /// assert_eq!(v.len(), 3);
+    /// assert_eq!(v.iter().copied().collect::<Vec<i32>>(), vec![0, 4, 5]);
+    ///
+    /// assert_eq!(v.push(8), 2);
+    /// assert_eq!(v.push(9), 3);
+    /// assert_eq!(v.push(10), 1);
+    /// assert_eq!(v.push(11), 6);
+    ///
+    /// let mut v: HoleyVec<i32> = (0..5).collect();
+    /// v.remove(1);
+    /// v.remove(3);
+    /// v.remove(4);
+    /// v.shrink_to_fit();
+    /// assert_eq!(v.index_upper_bound(), 3);
+    /// assert_eq!(v.capacity(), 3);
+    /// assert!(v.is_hole(1));
+    /// assert_eq!(v.next_index(), 1);
+    /// ```
+    pub fn shrink_to_fit(&mut self) {
+        self.truncate_holes(0);
+        self.vec.shrink_to_fit();
+        self.occupied.shrink_to_fit();
+    }
+
+    /// Remove empty elements from the end of the vector while more than `min_capacity`
+    /// elements are left and shrink the capacity of the underlying vector with a lower bound.
+    /// Values are never removed. The capacity remains at least as large as both
+    /// [HoleyVec::index_upper_bound] and `min_capacity`. Similar to [std::vec::Vec::shrink_to].
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v = HoleyVec::with_capacity(100);
+    /// v.push(1);
+    /// v.push(2);
+    /// v.push(3);
+    /// v.push(4);
+    /// v.remove(1);
+    /// v.remove(3);
+    ///
+    /// v.shrink_to(10);
+    /// assert!(v.capacity() >= 10 && v.capacity() < 100);
+    ///
+    /// v.shrink_to(0);
+    /// assert!(v.capacity() >= 3);
+    /// assert_eq!(v.index_upper_bound(), 3);
+    /// assert_eq!(v.iter().copied().collect::<Vec<i32>>(), vec![1, 3]);
+    /// assert_eq!(v.push(5), 1);
+    /// assert_eq!(v.push(6), 3);
+    ///
+    /// // Trailing holes are kept up to `min_capacity`
+    /// let mut v: HoleyVec<i32> = (0..100).collect();
+    /// for index in (10..100).rev() {
+    ///     v.remove(index);
+    /// }
+    /// v.remove(3);
+    /// v.shrink_to(20);
+    /// assert_eq!(v.index_upper_bound(), 20);
+    /// assert!(v.capacity() >= 20 && v.capacity() < 100);
+    /// assert_eq!(v.len(), 9);
+    /// assert_eq!(v.indices().collect::<Vec<usize>>(), vec![0, 1, 2, 4, 5, 6, 7, 8, 9]);
+    /// assert_eq!(v.push(3), 3);
+    /// assert_eq!(v.push(10), 10);
+    /// assert_eq!(v.push(20), 11);
+    ///
+    /// // Values are never removed
+    /// v.shrink_to(5);
+    /// assert_eq!(v.index_upper_bound(), 12);
+    /// assert_eq!(v.len(), 12);
+    /// assert!(v.capacity() >= 12);
+    /// ```
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        self.truncate_holes(min_capacity);
+        self.vec.shrink_to(min_capacity);
+        self.occupied.shrink_to(min_capacity.div_ceil(WORD_BITS));
+    }
+
+    /// Check if element by index is empty.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v = HoleyVec::new();
+    /// // No holes in empty vector
+    /// assert!(!v.is_hole(0));
+    ///
+    /// v.push(42);
+    /// assert!(!v.is_hole(0));
+    ///
+    /// v.remove(0);
+    /// assert!(v.is_hole(0));
+    /// ```
+    pub fn is_hole(&self, index: usize) -> bool {
+        matches!(self.vec.get(index), Some(Cell::Hole(_)))
+    }
+
+    /// Check if element by index is not empty. Unlike [HoleyVec::is_hole] it returns `false`
+    /// when index is out of bounds.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v = HoleyVec::new();
+    /// v.push(42);
+    /// v.push(43);
+    /// v.remove(1);
+    ///
+    /// assert!(v.contains_index(0));
+    /// assert!(!v.contains_index(1));
+    /// assert!(!v.contains_index(2));
+    /// assert!(!v.is_hole(2));
+    /// ```
+    pub fn contains_index(&self, index: usize) -> bool {
+        matches!(self.vec.get(index), Some(Cell::Value(_)))
+    }
+
+    /// Get value by index.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v = HoleyVec::new();
+    /// assert_eq!(v.get(0), None);
+    ///
+    /// v.push(42);
+    /// assert_eq!(v.get(0), Some(&42));
+    ///
+    /// v.remove(0);
+    /// assert_eq!(v.get(0), None);
+    /// ```
+    pub fn get(&self, index: usize) -> Option<&T> {
+        match self.vec.get(index) {
+            Some(Cell::Value(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Get the value with the lowest index paired with the index. Return `None` if vector has
+    /// no values.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..200).collect();
+    /// assert_eq!(v.first(), Some((0, &0)));
+    /// for index in 0..150 {
+    ///     v.remove(index);
+    /// }
+    /// assert_eq!(v.first(), Some((150, &150)));
+    /// v.clear();
+    /// assert_eq!(v.first(), None);
+    /// ```
+    pub fn first(&self) -> Option<(usize, &T)> {
+        self.iter_indexed().next()
+    }
+
+    /// Get the value with the greatest index paired with the index. Return `None` if vector has
+    /// no values. Holes at the end of the vector are skipped by words of the occupancy bitset.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..200).collect();
+    /// assert_eq!(v.last(), Some((199, &199)));
+    /// for index in 50..200 {
+    ///     v.remove(index);
+    /// }
+    /// v.remove(0);
+    /// assert_eq!(v.last(), Some((49, &49)));
+    /// for index in 1..50 {
+    ///     v.remove(index);
+    /// }
+    /// assert_eq!(v.last(), None);
+    /// ```
+    pub fn last(&self) -> Option<(usize, &T)> {
+        let (word, bits) = self.occupied.iter().enumerate().rev().find(|&(_, &bits)| bits != 0)?;
+        let index = word * WORD_BITS + (WORD_BITS - 1 - bits.leading_zeros() as usize);
+        self.get(index).map(|value| (index, value))
+    }
+
+    /// Return index of the first value in the order of indexes for which predicate returns
+    /// `true`. Return `None` when there is no such value. Predicate is not called after the
+    /// first match.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = [5, 20, 8, 30, 40].into_iter().collect();
+    /// v.remove(1);
+    /// let mut calls = 0;
+    /// assert_eq!(v.find_index(|&value| { calls += 1; value > 10 }), Some(3));
+    /// assert_eq!(calls, 3);
+    /// assert_eq!(v.find_index(|&value| value > 40), None);
+    /// ```
+    pub fn find_index<F: FnMut(&T) -> bool>(&self, mut f: F) -> Option<usize> {
+        self.iter_indexed().find(|(_, value)| f(value)).map(|(index, _)| index)
+    }
+
+    /// Return index of the first value equal to `value` in the order of indexes or `None` when
+    /// there is no such value. It takes time proportional to the number of values, thus it is
+    /// intended for occasional lookups.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<char> = "abcab".chars().collect();
+    /// v.remove(0);
+    /// v.remove(2);
+    /// assert_eq!(v.position_of(&'a'), Some(3));
+    /// assert_eq!(v.position_of(&'b'), Some(1));
+    /// assert_eq!(v.position_of(&'c'), None);
+    /// assert_eq!(v.position_of(&'z'), None);
+    /// ```
+    pub fn position_of(&self, value: &T) -> Option<usize> where T: PartialEq {
+        self.find_index(|item| item == value)
+    }
+
+    /// Get mutable value by index.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v = HoleyVec::new();
+    /// assert_eq!(v.get_mut(0), None);
+    ///
+    /// v.push(42);
+    /// v.get_mut(0).map(|v| *v = 24);
+    /// assert_eq!(v.get(0), Some(&24));
+    ///
+    /// v.remove(0);
+    /// assert_eq!(v.get_mut(0), None);
+    /// ```
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        match self.vec.get_mut(index) {
+            Some(Cell::Value(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Get value by index without checking that index is in bounds and element is not empty.
+    /// Checked by debug assertion only.
+    ///
+    /// # Safety
+    ///
+    /// Index must be less than [HoleyVec::index_upper_bound] and element must not be a hole,
+    /// i.e. [HoleyVec::contains_index] must return `true`. Otherwise the behavior is undefined.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..3).collect();
+    /// v.remove(1);
+    /// assert!(v.contains_index(2));
+    /// assert_eq!(unsafe { v.get_unchecked(2) }, &2);
+    /// ```
+    pub unsafe fn get_unchecked(&self, index: usize) -> &T {
+        debug_assert!(self.contains_index(index), "Index {} doesn't contain value", index);
+        match self.vec.get_unchecked(index) {
+            Cell::Value(value) => value,
+            Cell::Hole(_) => core::hint::unreachable_unchecked(),
+        }
+    }
+
+    /// Get mutable value by index without checking that index is in bounds and element is not
+    /// empty. Checked by debug assertion only.
+    ///
+    /// # Safety
+    ///
+    /// Index must be less than [HoleyVec::index_upper_bound] and element must not be a hole,
+    /// i.e. [HoleyVec::contains_index] must return `true`. Otherwise the behavior is undefined.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..3).collect();
+    /// v.remove(1);
+    /// assert!(v.contains_index(0));
+    /// unsafe { *v.get_unchecked_mut(0) = 10 };
+    /// assert_eq!(v.get(0), Some(&10));
+    ///
+    /// // Indexes validated once are used after the vector reallocates
+    /// let mut v: HoleyVec<String> = (0..100).map(|i| i.to_string()).collect();
+    /// v.retain(|index, _| index % 3 != 0);
+    /// let indices: Vec<usize> = v.indices().collect();
+    /// v.reserve(1000);
+    /// for &index in &indices {
+    ///     unsafe { v.get_unchecked_mut(index).push('!') };
+    /// }
+    /// for &index in &indices {
+    ///     assert_eq!(unsafe { v.get_unchecked(index) }, &format!("{}!", index));
+    /// }
+    /// ```
+    pub unsafe fn get_unchecked_mut(&mut self, index: usize) -> &mut T {
+        debug_assert!(self.contains_index(index), "Index {} doesn't contain value", index);
+        match self.vec.get_unchecked_mut(index) {
+            Cell::Value(value) => value,
+            Cell::Hole(_) => core::hint::unreachable_unchecked(),
+        }
+    }
+
+    /// Get several mutable values by indexes at once. Return `None` if any index is out of
+    /// bounds, is empty or repeated.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..4).collect();
+    /// v.remove(1);
+    ///
+    /// if let Some([a, b]) = v.get_disjoint_mut([3, 0]) {
+    ///     std::mem::swap(a, b);
+    /// }
+    /// assert_eq!(v.get(0), Some(&3));
+    /// assert_eq!(v.get(3), Some(&0));
+    ///
+    /// // Repeated index
+    /// assert_eq!(v.get_disjoint_mut([0, 2, 0]), None);
+    /// // Empty element
+    /// assert_eq!(v.get_disjoint_mut([0, 1]), None);
+    /// // Index out of bounds
+    /// assert_eq!(v.get_disjoint_mut([0, 4]), None);
+    ///
+    /// // Any number of indexes
+    /// let mut v: HoleyVec<i32> = (0..6).collect();
+    /// v.remove(2);
+    /// if let Some([a, b, c]) = v.get_disjoint_mut([5, 1, 3]) {
+    ///     *a += *b + *c;
+    ///     *b = 0;
+    /// }
+    /// assert_eq!(v.iter().copied().collect::<Vec<i32>>(), vec![0, 0, 3, 4, 9]);
+    /// assert_eq!(v.get_disjoint_mut([]), Some([]));
+    /// assert_eq!(v.get_disjoint_mut([0, 1, 3, 2]), None);
+    /// assert_eq!(v.get_disjoint_mut([4, 1, 3, 1]), None);
+    /// ```
+    pub fn get_disjoint_mut<const N: usize>(&mut self, indices: [usize; N])
+        -> Option<[&mut T; N]> {
+        if indices.iter().any(|&index| !self.contains_index(index)) {
+            return None;
+        }
+        let cells = self.vec.get_disjoint_mut(indices).ok()?;
+        Some(cells.map(|cell| match cell {
+            Cell::Value(value) => value,
+            Cell::Hole(_) => unreachable!(),
+        }))
+    }
+
+    /// Get two mutable values by indexes at once. Return `None` if any index is out of bounds,
+    /// is empty or indexes are equal. Same as [HoleyVec::get_disjoint_mut] with two indexes.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<String> = ["a", "b", "c"].into_iter().map(String::from).collect();
+    /// v.remove(1);
+    ///
+    /// if let Some((a, c)) = v.get2_mut(0, 2) {
+    ///     a.push_str(&std::mem::take(c));
+    /// }
+    /// if let Some((c, a)) = v.get2_mut(2, 0) {
+    ///     c.push_str(a);
+    /// }
+    /// assert_eq!(v.get(0).map(String::as_str), Some("ac"));
+    /// assert_eq!(v.get(2).map(String::as_str), Some("ac"));
+    ///
+    /// // Equal indexes
+    /// assert_eq!(v.get2_mut(0, 0), None);
+    /// // Empty element
+    /// assert_eq!(v.get2_mut(0, 1), None);
+    /// assert_eq!(v.get2_mut(1, 2), None);
+    /// // Index out of bounds
+    /// assert_eq!(v.get2_mut(3, 0), None);
+    /// ```
+    pub fn get2_mut(&mut self, i: usize, j: usize) -> Option<(&mut T, &mut T)> {
+        self.get_disjoint_mut([i, j]).map(|[a, b]| (a, b))
+    }
+
+    /// Exchange values by indexes `a` and `b`. The list of empty elements is not affected. Keys
+    /// of both values become invalid when indexes differ, see [Key]. Panics when any of the
+    /// elements is empty or index is out of bounds.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    /// use std::panic::{catch_unwind, AssertUnwindSafe};
+    ///
+    /// let mut v: HoleyVec<i32> = (0..4).collect();
+    /// v.remove(1);
+    /// v.swap(0, 2);
+    /// assert_eq!(v.get(0), Some(&2));
+    /// assert_eq!(v.get(2), Some(&0));
+    /// assert!(v.is_hole(1));
+    /// assert_eq!(v.get(3), Some(&3));
+    ///
+    /// v.swap(3, 3);
+    /// assert_eq!(v.get(3), Some(&3));
+    ///
+    /// let error = catch_unwind(AssertUnwindSafe(|| v.swap(0, 1))).unwrap_err();
+    /// assert_eq!(*error.downcast::<String>().unwrap(), "Index 1 is a hole, index upper bound is 4");
+    /// assert_eq!(v.get(0), Some(&2));
+    /// assert_eq!(v.push(4), 1);
+    ///
+    /// // Keys don't give access to the moved values
+    /// let mut v = HoleyVec::new();
+    /// let a = v.push_key('a');
+    /// let b = v.push_key('b');
+    /// v.swap(a.index(), a.index());
+    /// assert_eq!(v.get_key(&a), Some(&'a'));
+    /// v.swap(a.index(), b.index());
+    /// assert_eq!(v.get_key(&a), None);
+    /// assert_eq!(v.get_key(&b), None);
+    /// assert_eq!(v.get(a.index()), Some(&'b'));
+    /// ```
+    #[track_caller]
+    pub fn swap(&mut self, a: usize, b: usize) {
+        for index in [a, b] {
+            if !self.contains_index(index) {
+                self.missing_value(index);
+            }
+        }
+        if a != b {
+            self.vec.swap(a, b);
+            self.next_generation(a);
+            self.next_generation(b);
+        }
+    }
+
+    /// Push value to vector using the index of the first empty element (see
+    /// [HoleyVec::next_index]). Panics when there are no holes and the index upper bound
+    /// reaches the maximal value of the index type (see [Index]).
+    /// 
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v = HoleyVec::new();
+    ///
+    /// v.push(42);
+    /// assert_eq!(v.get(0), Some(&42));
+    ///
+    /// v.push(43);
+    /// assert_eq!(v.get(0), Some(&42));
+    /// assert_eq!(v.get(1), Some(&43));
+    ///
+    /// v.remove(0);
+    /// assert_eq!(v.get(0), None);
+    /// assert_eq!(v.get(1), Some(&43));
+    ///
+    /// v.push(24);
+    /// assert_eq!(v.get(0), Some(&24));
+    /// assert_eq!(v.get(1), Some(&43));
+    /// ```
+    #[track_caller]
+    pub fn push(&mut self, value: T) -> usize {
+        if self.first_hole == NO_HOLE {
+            let index = self.vec.len();
+            self.check_new_index(index);
+            self.vec.push(Cell::Value(value));
+            self.set_occupied(index);
+            self.len += 1;
+            index
+        } else {
+            let index = self.first_hole;
+            match self.vec[index] {
+                Cell::Hole(next_hole) => {
+                    self.first_hole = from_link(next_hole);
+                    self.vec[index] = Cell::Value(value);
+                },
+                _ => panic!("Unexpected state"),
+            }
+            self.set_occupied(index);
+            self.len += 1;
+            index
+        }
+    }
+
+    /// Push value returned by `f` to vector as [HoleyVec::push] does. Function `f` receives
+    /// the index of the value. The vector is not changed when `f` panics.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<String> = HoleyVec::new();
+    /// assert_eq!(v.push_with(|index| format!("item {}", index)), 0);
+    /// assert_eq!(v.push_with(|index| format!("item {}", index)), 1);
+    /// v.remove(0);
+    /// assert_eq!(v.push_with(|index| format!("reused {}", index)), 0);
+    /// assert_eq!(v.iter().cloned().collect::<Vec<String>>(), vec!["reused 0", "item 1"]);
+    ///
+    /// v.remove(1);
+    /// let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    ///     v.push_with(|_index| panic!("no value"))
+    /// }));
+    /// assert!(result.is_err());
+    /// assert_eq!(v.len(), 1);
+    /// assert!(v.is_hole(1));
+    /// assert_eq!(v.push("item 1".to_string()), 1);
+    /// assert_eq!(v.push("item 2".to_string()), 2);
+    /// ```
+    #[track_caller]
+    pub fn push_with<F: FnOnce(usize) -> T>(&mut self, f: F) -> usize {
+        let index = self.next_index();
+        if self.first_hole == NO_HOLE {
+            self.check_new_index(index);
+        }
+        self.push(f(index))
+    }
+
+    /// Push values to vector as [HoleyVec::push] does and return their indexes in the same
+    /// order. Capacity is reserved once using the lower bound of the iterator size. Same as
+    /// [Extend::extend] but returns the indexes.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..5).collect();
+    /// v.remove(3);
+    /// v.remove(1);
+    ///
+    /// assert_eq!(v.push_iter([10, 20, 30, 40]), vec![1, 3, 5, 6]);
+    /// assert_eq!(v.iter().copied().collect::<Vec<i32>>(), vec![0, 10, 2, 20, 4, 30, 40]);
+    /// assert!(v.push_iter(std::iter::empty()).is_empty());
+    /// ```
+    #[track_caller]
+    pub fn push_iter<I: IntoIterator<Item=T>>(&mut self, iter: I) -> Vec<usize> {
+        let iter = iter.into_iter();
+        let size = iter.size_hint().0;
+        self.reserve(size);
+        let mut indexes = Vec::with_capacity(size);
+        for value in iter {
+            indexes.push(self.push(value));
+        }
+        indexes
+    }
+
+    /// Put values into holes in ascending order of indexes until either holes or values run
+    /// out. Return the number of holes filled. The order in which the rest of holes are reused
+    /// is kept.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// // More values than holes
+    /// let mut v: HoleyVec<i32> = (0..5).collect();
+    /// v.remove(3);
+    /// v.remove(1);
+    /// let mut values = 10..20;
+    /// assert_eq!(v.fill_holes_with(&mut values), 2);
+    /// assert_eq!(values.next(), Some(12));
+    /// assert_eq!(v.iter().copied().collect::<Vec<i32>>(), vec![0, 10, 2, 11, 4]);
+    /// assert_eq!(v.hole_count(), 0);
+    /// assert_eq!(v.next_index(), 5);
+    ///
+    /// // Fewer values than holes
+    /// let mut v: HoleyVec<i32> = (0..8).collect();
+    /// v.remove(2);
+    /// v.remove(6);
+    /// v.remove(1);
+    /// v.remove(4);
+    /// assert_eq!(v.fill_holes_with([10, 20]), 2);
+    /// assert_eq!(v.indices().collect::<Vec<usize>>(), vec![0, 1, 2, 3, 5, 7]);
+    /// assert_eq!(v.get(1), Some(&10));
+    /// assert_eq!(v.get(2), Some(&20));
+    /// assert_eq!(v.hole_count(), 2);
+    /// assert_eq!(v.next_index(), 4);
+    /// assert_eq!(v.push(30), 4);
+    /// assert_eq!(v.push(40), 6);
+    /// assert_eq!(v.push(50), 8);
+    /// ```
+    pub fn fill_holes_with<I: IntoIterator<Item=T>>(&mut self, values: I) -> usize {
+        let filled: Vec<(usize, T)> = (0..self.vec.len())
+            .filter(|&index| self.is_hole(index))
+            .zip(values)
+            .collect();
+        let count = filled.len();
+        if let Some(&(last, _)) = filled.last() {
+            self.retain_holes(|index| index > last);
+        }
+        for (index, value) in filled {
+            self.vec[index] = Cell::Value(value);
+            self.set_occupied(index);
+        }
+        self.len += count;
+        count
+    }
+
+    /// Put clones of `value` into all holes. Return the number of holes filled.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<String> = HoleyVec::new();
+    /// v.insert(1, "a".to_string());
+    /// v.insert(4, "b".to_string());
+    /// assert_eq!(v.fill_holes(String::new()), 3);
+    /// assert_eq!(v.iter().cloned().collect::<Vec<String>>(), vec!["", "a", "", "", "b"]);
+    /// assert_eq!(v.hole_count(), 0);
+    /// assert_eq!(v.push("c".to_string()), 5);
+    /// assert_eq!(v.fill_holes(String::new()), 0);
+    /// ```
+    pub fn fill_holes(&mut self, value: T) -> usize where T: Clone {
+        let count = self.hole_count();
+        self.fill_holes_with(core::iter::repeat_n(value, count))
+    }
+
+    /// Push value to vector as [HoleyVec::push] does. Return the value back as an error when
+    /// there are no holes and a new element cannot be added because of the maximal index set
+    /// by [HoleyVec::with_max_index] or the maximal value of the index type (see [Index]).
+    /// Filling a hole always succeeds.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = HoleyVec::with_max_index(0);
+    /// assert_eq!(v.try_push(1), Ok(0));
+    /// assert_eq!(v.try_push(2), Err(2));
+    /// v.remove(0);
+    /// assert_eq!(v.try_push(3), Ok(0));
+    ///
+    /// let mut v: HoleyVec<(), u16> = std::iter::repeat(()).take(u16::MAX as usize).collect();
+    /// assert_eq!(v.try_push(()), Err(()));
+    /// ```
+    pub fn try_push(&mut self, value: T) -> Result<usize, T> {
+        if self.first_hole == NO_HOLE && !self.can_add_index(self.vec.len()) {
+            return Err(value);
+        }
+        Ok(self.push(value))
+    }
+
+    /// Put value by index. Return the previous value when element is not empty. When element
+    /// is a hole it is removed from the list of empty elements, it requires walking the list
+    /// thus it takes time proportional to the number of holes. When index is out of bounds the
+    /// vector is extended and new elements become holes, they are reused by [HoleyVec::push]
+    /// before other holes in ascending order. Panics when index is not less than the maximal
+    /// value of the index type (see [Index]).
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..4).collect();
+    /// v.remove(2);
+    /// v.remove(1);
+    /// v.remove(0);
+    ///
+    /// // Hole
+    /// assert_eq!(v.insert(1, 10), None);
+    /// assert_eq!(v.get(1), Some(&10));
+    /// assert_eq!(v.len(), 2);
+    ///
+    /// // Non-empty element
+    /// assert_eq!(v.insert(1, 11), Some(10));
+    /// assert_eq!(v.get(1), Some(&11));
+    /// assert_eq!(v.len(), 2);
+    ///
+    /// // Out of bounds
+    /// assert_eq!(v.insert(6, 60), None);
+    /// assert_eq!(v.get(6), Some(&60));
+    /// assert_eq!(v.len(), 3);
+    /// assert_eq!(v.index_upper_bound(), 7);
+    ///
+    /// assert_eq!(v.push(4), 4);
+    /// assert_eq!(v.push(5), 5);
+    /// assert_eq!(v.push(0), 0);
+    /// assert_eq!(v.push(2), 2);
+    /// assert_eq!(v.push(7), 7);
+    ///
+    /// // Far out of bounds interleaved with pushes
+    /// let mut v = HoleyVec::new();
+    /// assert_eq!(v.push(0), 0);
+    /// assert_eq!(v.insert(10_000, 1), None);
+    /// assert_eq!(v.hole_count(), 9_999);
+    /// assert_eq!(v.push(2), 1);
+    /// assert_eq!(v.push(3), 2);
+    /// assert_eq!(v.insert(20_000, 4), None);
+    /// assert_eq!(v.push(5), 10_001);
+    /// assert_eq!(v.insert(5, 6), None);
+    /// let holes = v.hole_count();
+    /// let mut indices: Vec<usize> = (0..holes).map(|_| v.push(7)).collect();
+    /// indices.sort();
+    /// assert_eq!(indices, (3..5).chain(6..10_000).chain(10_002..20_000).collect::<Vec<_>>());
+    /// assert_eq!(v.push(8), 20_001);
+    /// ```
+    #[track_caller]
+    pub fn insert(&mut self, index: usize, value: T) -> Option<T> {
+        if index >= self.vec.len() {
+            self.check_new_index(index);
+            self.grow_with_holes(index);
+            self.vec.push(Cell::Value(value));
+            self.set_occupied(index);
+            self.len += 1;
+            return None;
+        }
+        match &mut self.vec[index] {
+            Cell::Value(old) => Some(core::mem::replace(old, value)),
+            Cell::Hole(_) => {
+                self.unlink_hole(index);
+                self.vec[index] = Cell::Value(value);
+                self.set_occupied(index);
+                self.len += 1;
+                None
+            },
+        }
+    }
+
+    /// Put value by index which is less than [HoleyVec::index_upper_bound] as
+    /// [HoleyVec::insert] does. Return the previous value when element is not empty. Filling a
+    /// hole requires walking the list of empty elements, thus it takes time proportional to the
+    /// number of holes. Panics when index is out of bounds.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    /// use std::panic::{catch_unwind, AssertUnwindSafe};
+    ///
+    /// let mut v: HoleyVec<i32> = (0..6).collect();
+    /// v.remove(1);
+    /// v.remove(3);
+    /// v.remove(4);
+    /// assert_eq!(v.next_index(), 4);
+    ///
+    /// assert_eq!(v.put(0, 10), Some(0));
+    /// assert_eq!(v.put(3, 30), None);
+    /// assert_eq!(v.put(4, 40), None);
+    /// assert_eq!(v.next_index(), 1);
+    /// assert!(v.is_hole(v.next_index()));
+    ///
+    /// let error = catch_unwind(AssertUnwindSafe(|| v.put(6, 60))).unwrap_err();
+    /// assert_eq!(*error.downcast::<String>().unwrap(),
+    ///     "Index 6 is out of bounds, index upper bound is 6");
+    /// assert_eq!(v.push(1), 1);
+    /// assert_eq!(v.push(6), 6);
+    /// ```
+    #[track_caller]
+    pub fn put(&mut self, index: usize, value: T) -> Option<T> {
+        if index >= self.vec.len() {
+            self.missing_value(index);
+        }
+        self.insert(index, value)
+    }
+
+    /// Replace value by index and return the previous value. Unlike [HoleyVec::insert] it never
+    /// fills empty elements. The list of empty elements is not affected. Panics when element is
+    /// empty or index is out of bounds, see [HoleyVec::try_replace] for the fallible version.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    /// use std::panic::{catch_unwind, AssertUnwindSafe};
+    ///
+    /// let mut v: HoleyVec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+    /// v.remove(1);
+    ///
+    /// assert_eq!(v.replace(0, "x".to_string()), "a");
+    /// assert_eq!(v.get(0).map(String::as_str), Some("x"));
+    ///
+    /// let error = catch_unwind(AssertUnwindSafe(|| v.replace(1, "y".to_string()))).unwrap_err();
+    /// assert_eq!(*error.downcast::<String>().unwrap(), "Index 1 is a hole, index upper bound is 3");
+    /// assert!(v.is_hole(1));
+    /// assert_eq!(v.push("z".to_string()), 1);
+    /// ```
+    #[track_caller]
+    pub fn replace(&mut self, index: usize, value: T) -> T {
+        match self.try_replace(index, value) {
+            Ok(old) => old,
+            Err(_) => self.missing_value(index),
+        }
+    }
+
+    /// Replace value by index and return the previous value as [HoleyVec::replace] does. When
+    /// element is empty or index is out of bounds the vector is kept untouched and the value is
+    /// returned back as an error.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..3).collect();
+    /// v.remove(1);
+    ///
+    /// assert_eq!(v.try_replace(0, 10), Ok(0));
+    /// assert_eq!(v.get(0), Some(&10));
+    ///
+    /// // Hole
+    /// assert_eq!(v.try_replace(1, 11), Err(11));
+    /// assert!(v.is_hole(1));
+    ///
+    /// // Out of bounds
+    /// assert_eq!(v.try_replace(3, 13), Err(13));
+    /// assert_eq!(v.index_upper_bound(), 3);
+    /// assert_eq!(v.len(), 2);
+    /// ```
+    pub fn try_replace(&mut self, index: usize, value: T) -> Result<T, T> {
+        match self.get_mut(index) {
+            Some(old) => Ok(core::mem::replace(old, value)),
+            None => Err(value),
+        }
+    }
+
+    /// Get mutable value by index inserting the value returned by `f` when element is empty.
+    /// Function `f` is called only when value doesn't exist. The value is put into the vector
+    /// as [HoleyVec::insert] does.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..3).collect();
+    /// v.remove(1);
+    ///
+    /// let mut calls = 0;
+    /// *v.get_or_insert_with(1, || { calls += 1; 10 }) += 1;
+    /// assert_eq!(v.get(1), Some(&11));
+    /// *v.get_or_insert_with(1, || { calls += 1; 20 }) += 1;
+    /// assert_eq!(v.get(1), Some(&12));
+    /// assert_eq!(calls, 1);
+    ///
+    /// assert_eq!(v.get_or_insert_with(4, || 40), &mut 40);
+    /// assert!(v.is_hole(3));
+    ///
+    /// // Hole in the middle of the list of empty elements is unlinked
+    /// let mut v: HoleyVec<i32> = (0..5).collect();
+    /// v.remove(0);
+    /// v.remove(2);
+    /// v.remove(4);
+    /// assert_eq!(v.get_or_insert_with(2, || 20), &mut 20);
+    /// assert_eq!(v.push(5), 4);
+    /// assert_eq!(v.push(6), 0);
+    /// assert_eq!(v.push(7), 5);
+    /// ```
+    #[track_caller]
+    pub fn get_or_insert_with<F: FnOnce() -> T>(&mut self, index: usize, f: F) -> &mut T {
+        self.entry(index).or_insert_with(f)
+    }
+
+    /// Get the element by index for in-place manipulation. Index may be out of bounds, the
+    /// vector is extended by holes when the value is put into such element.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::{Entry, HoleyVec};
+    ///
+    /// let mut v: HoleyVec<i32> = (0..5).collect();
+    /// v.remove(1);
+    /// v.remove(3);
+    ///
+    /// match v.entry(0) {
+    ///     Entry::Occupied(mut entry) => {
+    ///         assert_eq!(entry.index(), 0);
+    ///         assert_eq!(entry.get(), &0);
+    ///         *entry.get_mut() += 1;
+    ///         assert_eq!(entry.insert(10), 1);
+    ///         *entry.into_mut() += 1;
+    ///     },
+    ///     Entry::Vacant(_) => unreachable!(),
+    /// }
+    /// assert_eq!(v.get(0), Some(&11));
+    ///
+    /// match v.entry(2) {
+    ///     Entry::Occupied(entry) => assert_eq!(entry.remove(), 2),
+    ///     Entry::Vacant(_) => unreachable!(),
+    /// }
+    /// assert!(v.is_hole(2));
+    ///
+    /// // Hole which is not the first one in the list of empty elements
+    /// assert_eq!(v.next_index(), 2);
+    /// match v.entry(3) {
+    ///     Entry::Vacant(entry) => {
+    ///         assert_eq!(entry.index(), 3);
+    ///         assert_eq!(entry.insert(30), &mut 30);
+    ///     },
+    ///     Entry::Occupied(_) => unreachable!(),
+    /// }
+    /// assert_eq!(v.get(3), Some(&30));
+    ///
+    /// // Out of bounds
+    /// match v.entry(6) {
+    ///     Entry::Vacant(entry) => *entry.insert(60) += 1,
+    ///     Entry::Occupied(_) => unreachable!(),
+    /// }
+    /// assert_eq!(v.get(6), Some(&61));
+    ///
+    /// assert_eq!(v.push(7), 5);
+    /// assert_eq!(v.push(8), 2);
+    /// assert_eq!(v.push(9), 1);
+    /// assert_eq!(v.push(10), 7);
+    /// ```
+    pub fn entry(&mut self, index: usize) -> Entry<'_, T, Idx> {
+        if self.contains_index(index) {
+            Entry::Occupied(OccupiedEntry{ vec: self, index })
+        } else {
+            Entry::Vacant(VacantEntry{ vec: self, index })
+        }
+    }
+
+    /// Get the element which is filled by the next [HoleyVec::push]. It allows knowing the index
+    /// before the value is constructed. The vector is borrowed while the entry exists, thus the
+    /// index cannot be taken by another value. Vector is not changed when entry is dropped
+    /// without inserting a value.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// struct Node { id: usize }
+    ///
+    /// let mut v: HoleyVec<Node> = HoleyVec::new();
+    /// v.push(Node{ id: 0 });
+    /// v.push(Node{ id: 1 });
+    /// v.remove(0);
+    ///
+    /// let entry = v.vacant_entry();
+    /// let id = entry.index();
+    /// assert_eq!(id, 0);
+    /// assert_eq!(entry.insert(Node{ id }).id, 0);
+    /// assert_eq!(v[0].id, 0);
+    ///
+    /// let entry = v.vacant_entry();
+    /// assert_eq!(entry.index(), 2);
+    /// drop(entry);
+    /// assert_eq!(v.len(), 2);
+    /// assert_eq!(v.index_upper_bound(), 2);
+    /// assert_eq!(v.push(Node{ id: 2 }), 2);
+    /// ```
+    pub fn vacant_entry(&mut self) -> VacantEntry<'_, T, Idx> {
+        let index = self.next_index();
+        VacantEntry{ vec: self, index }
+    }
+
+    /// Push value to vector without growing the underlying vector. Return an index of the
+    /// value when there is an empty element or spare capacity. Otherwise the value is returned
+    /// back as an error. The value is returned back as well when a new element cannot be added
+    /// because of the maximal index (see [HoleyVec::try_push]), thus the method never panics.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v = HoleyVec::with_capacity(2);
+    /// let capacity = v.capacity();
+    /// for i in 0..capacity {
+    ///     assert_eq!(v.push_within_capacity(i), Ok(i));
+    /// }
+    /// assert_eq!(v.push_within_capacity(42), Err(42));
+    /// assert_eq!(v.capacity(), capacity);
+    ///
+    /// v.remove(1);
+    /// assert_eq!(v.push_within_capacity(42), Ok(1));
+    /// assert_eq!(v.capacity(), capacity);
+    ///
+    /// let mut v = HoleyVec::with_max_index(0);
+    /// v.reserve(10);
+    /// assert_eq!(v.push_within_capacity('a'), Ok(0));
+    /// assert_eq!(v.push_within_capacity('b'), Err('b'));
+    ///
+    /// let mut v: HoleyVec<(), u16> = std::iter::repeat(()).take(u16::MAX as usize).collect();
+    /// v.reserve(10);
+    /// assert_eq!(v.push_within_capacity(()), Err(()));
+    /// ```
+    pub fn push_within_capacity(&mut self, value: T) -> Result<usize, T> {
+        if self.first_hole == NO_HOLE
+            && (self.vec.len() == self.vec.capacity() || !self.can_add_index(self.vec.len())) {
+            Err(value)
+        } else {
+            Ok(self.push(value))
+        }
+    }
+
+    /// Remove value by index.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v = HoleyVec::new();
+    ///
+    /// v.push(42);
+    /// v.push(24);
+    /// assert_eq!(v.get(0), Some(&42));
+    /// assert_eq!(v.get(1), Some(&24));
+    ///
+    /// v.remove(0);
+    /// assert_eq!(v.get(0), None);
+    /// assert_eq!(v.get(1), Some(&24));
+    /// ```
+    ///
+    /// Panics when element is empty or index is out of bounds. The vector is kept untouched in
+    /// this case:
+    /// ```
+    /// use holeyvec::HoleyVec;
+    /// use std::panic::{catch_unwind, AssertUnwindSafe};
+    ///
+    /// let mut v: HoleyVec<i32> = (0..4).collect();
+    /// v.remove(1);
+    /// v.remove(2);
+    ///
+    /// fn message(v: &mut HoleyVec<i32>, index: usize) -> String {
+    ///     let error = catch_unwind(AssertUnwindSafe(|| v.remove(index))).unwrap_err();
+    ///     *error.downcast::<String>().unwrap()
+    /// }
+    /// assert_eq!(message(&mut v, 1), "Index 1 is a hole, index upper bound is 4");
+    /// assert_eq!(message(&mut v, 2), "Index 2 is a hole, index upper bound is 4");
+    /// assert_eq!(message(&mut v, 4), "Index 4 is out of bounds, index upper bound is 4");
+    /// assert_eq!(v.len(), 2);
+    /// assert_eq!(v.next_index(), 2);
+    /// assert_eq!(v.push(4), 2);
+    /// assert_eq!(v.push(5), 1);
+    /// assert_eq!(v.push(6), 4);
+    /// ```
+    #[track_caller]
+    pub fn remove(&mut self, index: usize) -> T {
+        match self.try_remove(index) {
+            Some(value) => value,
+            None => self.missing_value(index),
+        }
+    }
+
+    /// Remove value by index if it exists. Return `None` and keep the vector untouched if
+    /// element is empty or index is out of bounds.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v = HoleyVec::new();
+    ///
+    /// v.push(42);
+    /// v.push(24);
+    /// assert_eq!(v.try_remove(0), Some(42));
+    /// assert_eq!(v.get(0), None);
+    /// assert_eq!(v.get(1), Some(&24));
+    ///
+    /// // Empty element
+    /// assert_eq!(v.try_remove(0), None);
+    /// assert_eq!(v.len(), 1);
+    ///
+    /// // Index out of bounds
+    /// assert_eq!(v.try_remove(2), None);
+    /// assert_eq!(v.len(), 1);
+    ///
+    /// assert_eq!(v.push(43), 0);
+    /// assert_eq!(v.push(44), 2);
+    ///
+    /// // The list of empty elements is intact after failed removal
+    /// let mut v: HoleyVec<i32> = (0..5).collect();
+    /// v.remove(1);
+    /// v.remove(3);
+    /// v.remove(2);
+    /// assert_eq!(v.try_remove(3), None);
+    /// assert_eq!(v.try_remove(2), None);
+    /// assert_eq!(v.try_remove(5), None);
+    /// assert_eq!(v.push(5), 2);
+    /// assert_eq!(v.push(6), 3);
+    /// assert_eq!(v.push(7), 1);
+    /// assert_eq!(v.push(8), 5);
+    /// ```
+    pub fn try_remove(&mut self, index: usize) -> Option<T> {
+        match self.vec.get_mut(index) {
+            Some(cell @ Cell::Value(_)) => {
+                let value = core::mem::replace(cell, Cell::Hole(Idx::MAX));
+                self.link_hole(index);
+                self.clear_occupied(index);
+                self.len -= 1;
+                self.next_generation(index);
+                match value {
+                    Cell::Value(value) => Some(value),
+                    Cell::Hole(_) => unreachable!(),
+                }
+            },
+            _ => None,
+        }
+    }
+
+    /// Remove the value with the greatest index and return it paired with the index. Return
+    /// `None` if vector has no values. Holes which become trailing are removed from the end of
+    /// the vector. It scans the vector from the end thus it takes time proportional to the index
+    /// upper bound in the worst case.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..5).collect();
+    /// v.remove(1);
+    /// v.remove(3);
+    /// assert_eq!(v.pop(), Some((4, 4)));
+    /// assert_eq!(v.index_upper_bound(), 3);
+    /// assert_eq!(v.pop(), Some((2, 2)));
+    /// assert_eq!(v.index_upper_bound(), 1);
+    /// assert_eq!(v.push(5), 1);
+    ///
+    /// assert_eq!(v.pop(), Some((1, 5)));
+    /// assert_eq!(v.pop(), Some((0, 0)));
+    /// assert_eq!(v.pop(), None);
+    /// assert!(v.is_empty());
+    /// assert_eq!(v.index_upper_bound(), 0);
+    /// ```
+    pub fn pop(&mut self) -> Option<(usize, T)> {
+        let (index, _) = self.last()?;
+        self.unlink_holes_from(index);
+        self.vec.truncate(index + 1);
+        let value = match self.vec.pop() {
+            Some(Cell::Value(value)) => value,
+            _ => unreachable!(),
+        };
+        self.clear_occupied(index);
+        self.len -= 1;
+        self.next_generation(index);
+        self.truncate_holes(0);
+        Some((index, value))
+    }
+
+    /// Remove the value with the lowest index and return it paired with the index. Return
+    /// `None` if vector has no values. The element becomes a hole and other values keep their
+    /// indexes. It scans the vector from the start thus it takes time proportional to the index
+    /// of the value found.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..6).collect();
+    /// v.remove(0);
+    /// v.remove(2);
+    /// v.remove(3);
+    /// assert_eq!(v.pop_first(), Some((1, 1)));
+    /// assert_eq!(v.pop_first(), Some((4, 4)));
+    /// assert_eq!(v.pop_first(), Some((5, 5)));
+    /// assert_eq!(v.pop_first(), None);
+    /// assert!(v.is_empty());
+    /// assert_eq!(v.hole_count(), 6);
+    /// assert_eq!((0..6).map(|i| v.push(i)).collect::<Vec<usize>>(), vec![5, 4, 1, 3, 2, 0]);
+    ///
+    /// let mut v = HoleyVec::new();
+    /// v.insert(1000, 'a');
+    /// assert_eq!(v.pop_first(), Some((1000, 'a')));
+    /// assert_eq!(v.index_upper_bound(), 1001);
+    /// assert_eq!(v.next_index(), 1000);
+    /// ```
+    pub fn pop_first(&mut self) -> Option<(usize, T)> {
+        let (index, _) = self.first()?;
+        self.try_remove(index).map(|value| (index, value))
+    }
+
+    /// Remove value by index and move the value with the greatest index into its place as
+    /// [Vec::swap_remove] does. Return the removed value and the previous index of the moved
+    /// value. The element the value is moved from becomes a hole. When `index` is the greatest
+    /// index of a value it is removed as [HoleyVec::remove] does and `None` is returned as the
+    /// previous index. It scans the vector from the end thus it takes time proportional to the
+    /// number of trailing holes. Panics when element is empty or index is out of bounds.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..6).collect();
+    /// v.remove(5);
+    /// v.remove(2);
+    /// assert_eq!(v.swap_remove_fill(1), (1, Some(4)));
+    /// assert_eq!(v.get(1), Some(&4));
+    /// assert!(v.is_hole(4));
+    /// assert_eq!(v.swap_remove_fill(3), (3, None));
+    /// assert_eq!(v.iter_indexed().collect::<Vec<_>>(), vec![(0, &0), (1, &4)]);
+    ///
+    /// assert_eq!(v.push(6), 3);
+    /// assert_eq!(v.push(7), 4);
+    /// assert_eq!(v.push(8), 2);
+    /// assert_eq!(v.push(9), 5);
+    /// assert_eq!(v.hole_count(), 0);
+    /// ```
+    #[track_caller]
+    pub fn swap_remove_fill(&mut self, index: usize) -> (T, Option<usize>) {
+        if !self.contains_index(index) {
+            self.missing_value(index);
+        }
+        let last = match self.last() {
+            Some((last, _)) if last != index => last,
+            _ => return (self.remove(index), None),
+        };
+        self.vec.swap(index, last);
+        self.next_generation(index);
+        (self.remove(last), Some(last))
+    }
+
+    /// Remove the first value equal to `value` in the order of indexes. Return the removed
+    /// value paired with its index or `None` when there is no such value. It takes time
+    /// proportional to the index upper bound in the worst case.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<&str> = ["a", "b", "c", "b"].into_iter().collect();
+    /// assert_eq!(v.remove_value(&"b"), Some((1, "b")));
+    /// assert_eq!(v.remove_value(&"b"), Some((3, "b")));
+    /// assert_eq!(v.remove_value(&"b"), None);
+    /// assert_eq!(v.len(), 2);
+    /// assert_eq!(v.push("d"), 3);
+    /// ```
+    pub fn remove_value(&mut self, value: &T) -> Option<(usize, T)> where T: PartialEq {
+        let index = self.position_of(value)?;
+        self.try_remove(index).map(|value| (index, value))
+    }
+
+    /// Remove all values equal to `value` and return the number of values removed, see
+    /// [HoleyVec::retain].
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = [1, 2, 1, 3, 1].into_iter().collect();
+    /// assert_eq!(v.remove_all_values(&1), 3);
+    /// assert_eq!(v.remove_all_values(&1), 0);
+    /// assert_eq!(v.iter_indexed().collect::<Vec<_>>(), vec![(1, &2), (3, &3)]);
+    /// ```
+    pub fn remove_all_values(&mut self, value: &T) -> usize where T: PartialEq {
+        self.retain_mut(|_, other| other != value)
+    }
+
+    /// Remove values which are equal to a value with a lower index and return the number of
+    /// values removed. The kept values don't change their indexes, removed elements become
+    /// holes. Requires `std` feature.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<&str> = ["a", "b", "a", "c", "b", "a"].into_iter().collect();
+    /// v.remove(1);
+    /// assert_eq!(v.dedup_values(), 2);
+    /// assert_eq!(v.iter_indexed().collect::<Vec<_>>(), vec![(0, &"a"), (3, &"c"), (4, &"b")]);
+    /// assert_eq!(v.dedup_values(), 0);
+    /// assert_eq!(v.push("d"), 5);
+    ///
+    /// let mut v: HoleyVec<i32> = std::iter::repeat(7).take(5).collect();
+    /// assert_eq!(v.dedup_values(), 4);
+    /// assert_eq!(v.iter_indexed().collect::<Vec<_>>(), vec![(0, &7)]);
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn dedup_values(&mut self) -> usize where T: Eq + core::hash::Hash {
+        let mut seen = std::collections::HashSet::new();
+        let duplicates: Vec<usize> = self.iter_indexed()
+            .filter(|&(_, value)| !seen.insert(value))
+            .map(|(index, _)| index)
+            .collect();
+        for &index in duplicates.iter() {
+            self.try_remove(index);
+        }
+        duplicates.len()
+    }
+
+    /// Remove values by indexes as [HoleyVec::try_remove] does and return the removed values in
+    /// the order of `indices`. Empty elements, indexes out of bounds and repeated indexes give
+    /// `None` and don't affect the vector.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..5).collect();
+    /// v.remove(3);
+    /// assert_eq!(v.remove_many(&[4, 1, 3, 1, 7]), vec![Some(4), Some(1), None, None, None]);
+    /// assert_eq!(v.iter_indexed().collect::<Vec<_>>(), vec![(0, &0), (2, &2)]);
+    ///
+    /// assert_eq!(v.push(5), 1);
+    /// assert_eq!(v.push(6), 4);
+    /// assert_eq!(v.push(7), 3);
+    /// assert_eq!(v.push(8), 5);
+    /// ```
+    pub fn remove_many(&mut self, indices: &[usize]) -> Vec<Option<T>> {
+        indices.iter().map(|&index| self.try_remove(index)).collect()
+    }
+
+    /// Push value to vector as [HoleyVec::push] does and return the [Key] of the value.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v = HoleyVec::new();
+    /// let key = v.push_key(42);
+    /// assert_eq!(key.index(), 0);
+    /// assert_eq!(v.get_key(&key), Some(&42));
+    ///
+    /// // Keys are detected as stale after any way of removing the value
+    /// let mut v: HoleyVec<i32> = (0..10).collect();
+    /// v.remove(3);
+    /// let low = v.push_key(3);
+    /// assert_eq!(low.index(), 3);
+    /// let high = v.push_key(10);
+    /// assert_eq!(high.index(), 10);
+    /// v.truncate(5);
+    /// v.extend(5..11);
+    /// assert_eq!(v.get(10), Some(&10));
+    /// assert_eq!(v.get_key(&high), None);
+    /// assert_eq!(v.get_key(&low), Some(&3));
+    /// v.clear();
+    /// v.extend(0..11);
+    /// assert_eq!(v.get_key(&low), None);
+    /// let key = v.push_key(11);
+    /// drop(v.drain());
+    /// v.extend(0..12);
+    /// assert_eq!(v.get_key(&key), None);
+    /// ```
+    pub fn push_key(&mut self, value: T) -> Key {
+        let index = self.push(value);
+        if index >= self.generations.len() {
+            self.generations.resize(index + 1, 0);
+        }
+        Key{ index, generation: self.generation(index) }
+    }
+
+    /// Get value by key. Return `None` when the value was removed even if the element was
+    /// reused after that.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v = HoleyVec::new();
+    /// let old = v.push_key(42);
+    /// v.remove(old.index());
+    /// assert_eq!(v.get_key(&old), None);
+    ///
+    /// let new = v.push_key(24);
+    /// assert_eq!(new.index(), old.index());
+    /// assert_eq!(v.get_key(&old), None);
+    /// assert_eq!(v.get_key(&new), Some(&24));
+    /// assert_eq!(v.get(old.index()), Some(&24));
+    ///
+    /// v.clear();
+    /// v.push(1);
+    /// assert_eq!(v.get_key(&new), None);
+    /// ```
+    pub fn get_key(&self, key: &Key) -> Option<&T> {
+        if self.generation(key.index) == key.generation {
+            self.get(key.index)
+        } else {
+            None
+        }
+    }
+
+    /// Remove value by key. Return `None` and keep the vector untouched when the value was
+    /// already removed even if the element was reused after that.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v = HoleyVec::new();
+    /// let old = v.push_key(42);
+    /// assert_eq!(v.remove_key(&old), Some(42));
+    /// assert_eq!(v.remove_key(&old), None);
+    ///
+    /// let new = v.push_key(24);
+    /// assert_eq!(v.remove_key(&old), None);
+    /// assert_eq!(v.get_key(&new), Some(&24));
+    /// assert_eq!(v.remove_key(&new), Some(24));
+    /// ```
+    pub fn remove_key(&mut self, key: &Key) -> Option<T> {
+        if self.generation(key.index) == key.generation {
+            self.try_remove(key.index)
+        } else {
+            None
+        }
+    }
+
+    /// Remove all values from the vector. Capacity of the underlying vector is kept intact.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v = HoleyVec::new();
+    /// v.push(1);
+    /// v.push(2);
+    /// v.push(3);
+    /// v.remove(1);
+    /// let capacity = v.capacity();
+    ///
+    /// v.clear();
+    /// assert_eq!(v.len(), 0);
+    /// assert_eq!(v.next_index(), 0);
+    /// assert_eq!(v.index_upper_bound(), 0);
+    /// assert_eq!(v.capacity(), capacity);
+    /// assert_eq!(v.iter().next(), None);
+    ///
+    /// assert_eq!(v.push(4), 0);
+    /// assert_eq!(v.push(5), 1);
+    /// ```
+    ///
+    /// Each value is dropped exactly once:
+    /// ```
+    /// use holeyvec::HoleyVec;
+    /// use std::rc::Rc;
+    ///
+    /// let value = Rc::new(42);
+    /// let mut v = HoleyVec::new();
+    /// v.push(value.clone());
+    /// v.push(value.clone());
+    /// v.push(value.clone());
+    /// drop(v.remove(1));
+    /// assert_eq!(Rc::strong_count(&value), 3);
+    ///
+    /// v.clear();
+    /// assert_eq!(Rc::strong_count(&value), 1);
+    /// ```
+    pub fn clear(&mut self) {
+        self.next_generations(0);
+        self.vec.clear();
+        self.occupied.clear();
+        self.first_hole = NO_HOLE;
+        self.len = 0;
+    }
+
+    /// Keep only elements for which predicate returns `true`. Predicate is called for each
+    /// non-empty element in ascending order of indexes, it receives the index and a reference to
+    /// the value. Removed elements become holes, indexes of the kept elements are not changed.
+    /// Each element is removed right after predicate returns, thus vector stays consistent when
+    /// predicate panics.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..6).collect();
+    /// v.remove(2);
+    ///
+    /// v.retain(|_index, value| *value % 2 == 0);
+    /// assert_eq!(v.len(), 2);
+    /// assert_eq!(v.indices().collect::<Vec<_>>(), vec![0, 4]);
+    /// assert_eq!(v.iter().copied().collect::<Vec<i32>>(), vec![0, 4]);
+    ///
+    /// v.retain(|index, _value| index > 0);
+    /// assert_eq!(v.iter_indexed().collect::<Vec<_>>(), vec![(4, &4)]);
+    ///
+    /// // Retain everything, alternating elements and nothing
+    /// let mut v: HoleyVec<i32> = (0..6).collect();
+    /// v.retain(|_index, _value| true);
+    /// assert_eq!(v.len(), 6);
+    /// v.retain(|index, _value| index % 2 == 1);
+    /// assert_eq!(v.indices().collect::<Vec<_>>(), vec![1, 3, 5]);
+    /// v.retain(|_index, _value| false);
+    /// assert!(v.is_empty());
+    /// assert_eq!(v.hole_count(), 6);
+    ///
+    /// // Panicking predicate
+    /// let mut v: HoleyVec<i32> = (0..4).collect();
+    /// let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    ///     v.retain(|index, _value| if index == 2 { panic!() } else { false })
+    /// }));
+    /// assert!(result.is_err());
+    /// assert_eq!(v.indices().collect::<Vec<_>>(), vec![2, 3]);
+    /// assert_eq!(v.push(4), 1);
+    /// assert_eq!(v.push(5), 0);
+    /// assert_eq!(v.push(6), 4);
+    /// ```
+    pub fn retain<F: FnMut(usize, &T) -> bool>(&mut self, mut f: F) {
+        self.retain_mut(|index, value| f(index, value));
+    }
+
+    /// Keep only elements for which predicate returns `true` as [HoleyVec::retain] does, but
+    /// pass a mutable reference to the value to the predicate. Return the number of elements
+    /// removed.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut ttl: HoleyVec<u32> = [1, 3, 2, 1].into_iter().collect();
+    /// ttl.remove(2);
+    ///
+    /// assert_eq!(ttl.retain_mut(|_index, ttl| { *ttl -= 1; *ttl > 0 }), 2);
+    /// assert_eq!(ttl.iter_indexed().collect::<Vec<_>>(), vec![(1, &2)]);
+    /// assert_eq!(ttl.retain_mut(|_index, _ttl| true), 0);
+    /// assert_eq!(ttl.push(5), 3);
+    /// assert_eq!(ttl.push(5), 0);
+    /// assert_eq!(ttl.push(5), 2);
+    /// ```
+    pub fn retain_mut<F: FnMut(usize, &mut T) -> bool>(&mut self, mut f: F) -> usize {
+        let len = self.len;
+        for index in 0..self.vec.len() {
+            if let Cell::Value(value) = &mut self.vec[index] {
+                if !f(index, value) {
+                    self.try_remove(index);
+                }
+            }
+        }
+        len - self.len
+    }
+
+    /// Return an iterator which removes elements for which predicate returns `true` and yields
+    /// the removed values paired with their indexes. Predicate is called for each non-empty
+    /// element in ascending order of indexes as [HoleyVec::retain] does. Removed elements become
+    /// holes, indexes of the kept elements are not changed. When iterator is dropped before the
+    /// end the elements not visited are kept. Vector stays consistent when predicate panics.
+    /// Similar to [std::vec::Vec::extract_if].
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..8).collect();
+    /// v.remove(4);
+    ///
+    /// let odd: Vec<(usize, i32)> = v.extract_if(|_index, value| *value % 2 == 1).collect();
+    /// assert_eq!(odd, vec![(1, 1), (3, 3), (5, 5), (7, 7)]);
+    /// assert_eq!(v.iter_indexed().collect::<Vec<_>>(), vec![(0, &0), (2, &2), (6, &6)]);
+    ///
+    /// let mut extract = v.extract_if(|_index, _value| true);
+    /// assert_eq!(extract.next(), Some((0, 0)));
+    /// drop(extract);
+    /// assert_eq!(v.iter_indexed().collect::<Vec<_>>(), vec![(2, &2), (6, &6)]);
+    /// assert_eq!(v.push(8), 0);
+    ///
+    /// // Panicking predicate
+    /// let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    ///     v.extract_if(|index, _value| if index == 2 { panic!() } else { true }).count()
+    /// }));
+    /// assert!(result.is_err());
+    /// assert_eq!(v.iter_indexed().collect::<Vec<_>>(), vec![(2, &2), (6, &6)]);
+    /// assert_eq!(v.push(9), 0);
+    /// assert_eq!(v.push(10), 7);
+    /// ```
+    pub fn extract_if<F: FnMut(usize, &mut T) -> bool>(&mut self, f: F)
+        -> ExtractIf<'_, T, F, Idx> {
+        ExtractIf::new(self, f)
+    }
+
+    /// Build vector from values paired with indexes, see [HoleyVec::from_entries].
+    fn collect_entries<I: IntoIterator<Item=(usize, T)>>(entries: I) -> Self {
+        let mut vec = Vec::new();
+        let mut len = 0;
+        for (index, value) in entries {
+            if index >= vec.len() {
+                Self::check_upper_bound(index + 1);
+                vec.resize_with(index + 1, || Cell::Hole(Idx::MAX));
+            }
+            if let Cell::Hole(_) = vec[index] {
+                len += 1;
+            }
+            vec[index] = Cell::Value(value);
+        }
+        let mut result = Self{ len, vec, ..Self::default() };
+        result.link_holes();
+        result.fill_occupied();
+        result
+    }
+
+    /// Mark element as non-empty in the occupancy bitset.
+    fn set_occupied(&mut self, index: usize) {
+        let word = index / WORD_BITS;
+        if word >= self.occupied.len() {
+            self.occupied.resize(word + 1, 0);
+        }
+        self.occupied[word] |= 1 << (index % WORD_BITS);
+    }
+
+    /// Mark element as empty in the occupancy bitset.
+    fn clear_occupied(&mut self, index: usize) {
+        self.occupied[index / WORD_BITS] &= !(1 << (index % WORD_BITS));
+    }
+
+    /// Remove elements with indexes greater or equal to `upper_bound` from the occupancy bitset.
+    fn truncate_occupied(&mut self, upper_bound: usize) {
+        let words = upper_bound.div_ceil(WORD_BITS);
+        self.occupied.truncate(words);
+        if !upper_bound.is_multiple_of(WORD_BITS) {
+            if let Some(last) = self.occupied.get_mut(words - 1) {
+                *last &= (1 << (upper_bound % WORD_BITS)) - 1;
+            }
+        }
+    }
+
+    /// Build the occupancy bitset from scratch.
+    fn fill_occupied(&mut self) {
+        self.occupied.clear();
+        self.occupied.resize(self.vec.len().div_ceil(WORD_BITS), 0);
+        for (index, cell) in self.vec.iter().enumerate() {
+            if let Cell::Value(_) = cell {
+                self.occupied[index / WORD_BITS] |= 1 << (index % WORD_BITS);
+            }
+        }
+    }
+
+    /// Return the number of values which can be pushed: holes and new elements up to the
+    /// maximal index.
+    fn room(&self) -> usize {
+        let max_index = self.max_index.min(Idx::MAX.into_usize() - 1);
+        let new = (max_index + 1).saturating_sub(self.vec.len());
+        self.hole_count().saturating_add(new)
+    }
+
+    /// Check if element with `index` can be added, [HoleyVec::check_new_index] panics otherwise.
+    fn can_add_index(&self, index: usize) -> bool {
+        index <= self.max_index && index < Idx::MAX.into_usize()
+    }
+
+    /// Panic when element with `index` cannot be added, see [HoleyVec::with_max_index].
+    #[track_caller]
+    fn check_new_index(&self, index: usize) {
+        if index > self.max_index {
+            panic!("Index {} exceeds the maximal index {}", index, self.max_index);
+        }
+        Self::check_upper_bound(index + 1);
+    }
+
+    /// Panic when holes with indexes up to `upper_bound` cannot be linked using `Idx` type.
+    #[track_caller]
+    fn check_upper_bound(upper_bound: usize) {
+        if upper_bound > Idx::MAX.into_usize() {
+            panic!("Index upper bound {} exceeds the maximal index {:?} of the index type",
+                upper_bound, Idx::MAX);
+        }
+    }
+
+    /// Rebuild the list of empty elements so that holes are reused in ascending order of
+    /// indexes.
+    fn link_holes(&mut self) {
+        let mut next_hole = NO_HOLE;
+        for index in (0..self.vec.len()).rev() {
+            if let Cell::Hole(next) = &mut self.vec[index] {
+                *next = to_link(next_hole);
+                next_hole = index;
+            }
+        }
+        self.first_hole = next_hole;
+    }
+
+    /// Panic reporting that there is no value by index.
+    #[track_caller]
+    fn missing_value(&self, index: usize) -> ! {
+        if index < self.vec.len() {
+            panic!("Index {} is a hole, index upper bound is {}", index, self.vec.len());
+        } else {
+            panic!("Index {} is out of bounds, index upper bound is {}", index, self.vec.len());
+        }
+    }
+
+    /// Change generation of the element to invalidate keys of its value. Elements without
+    /// tracked generation have no keys, thus they are skipped.
+    fn next_generation(&mut self, index: usize) {
+        if let Some(generation) = self.generations.get_mut(index) {
+            *generation = generation.wrapping_add(1);
+        }
+    }
+
+    /// Change generations of non-empty elements starting from `from` index. Return number of
+    /// such elements.
+    fn next_generations(&mut self, from: usize) -> usize {
+        for index in from..self.vec.len().min(self.generations.len()) {
+            if let Cell::Value(_) = self.vec[index] {
+                self.next_generation(index);
+            }
+        }
+        self.count_values_from(from)
+    }
+
+    /// Return number of non-empty elements starting from `from` index using the occupancy
+    /// bitset.
+    fn count_values_from(&self, from: usize) -> usize {
+        let first = from / WORD_BITS;
+        let mask = !((1 << (from % WORD_BITS)) - 1);
+        self.occupied.iter().skip(first).enumerate()
+            .map(|(word, &bits)| if word == 0 { bits & mask } else { bits })
+            .map(|bits| bits.count_ones() as usize)
+            .sum()
+    }
+
+    /// Return generation of the element.
+    fn generation(&self, index: usize) -> u32 {
+        self.generations.get(index).copied().unwrap_or(0)
+    }
+
+    /// Extend the vector by holes up to `new_upper_bound`. New holes are put at the beginning
+    /// of the list of empty elements in ascending order, or at the end of the list when holes
+    /// are reused starting from the lowest index.
+    fn grow_with_holes(&mut self, new_upper_bound: usize) {
+        let upper_bound = self.vec.len();
+        if new_upper_bound <= upper_bound {
+            return;
+        }
+        let (next_hole, prev_hole) = match self.reuse {
+            Reuse::LastRemoved => (self.first_hole, None),
+            Reuse::LowestIndex => (NO_HOLE, self.prev_hole(upper_bound)),
+        };
+        self.vec.extend((upper_bound + 1..new_upper_bound).map(|next| Cell::Hole(to_link(next))));
+        self.vec.push(Cell::Hole(to_link(next_hole)));
+        let words = new_upper_bound.div_ceil(WORD_BITS);
+        if words > self.occupied.len() {
+            self.occupied.resize(words, 0);
+        }
+        match prev_hole {
+            None => self.first_hole = upper_bound,
+            Some(prev) => self.vec[prev] = Cell::Hole(to_link(upper_bound)),
+        }
+    }
+
+    /// Add hole to the list of empty elements. The hole is put at the beginning of the list or
+    /// after the previous hole when holes are reused starting from the lowest index.
+    fn link_hole(&mut self, index: usize) {
+        let prev_hole = match self.reuse {
+            Reuse::LastRemoved => None,
+            Reuse::LowestIndex => self.prev_hole(index),
+        };
+        let next = match prev_hole {
+            None => core::mem::replace(&mut self.first_hole, index),
+            Some(prev) => match &mut self.vec[prev] {
+                Cell::Hole(link) => from_link(core::mem::replace(link, to_link(index))),
+                _ => panic!("Unexpected state"),
+            },
+        };
+        self.vec[index] = Cell::Hole(to_link(next));
+    }
+
+    /// Return the greatest index of a hole which is less than `index` using the occupancy
+    /// bitset. Index should not be greater than [HoleyVec::index_upper_bound].
+    fn prev_hole(&self, index: usize) -> Option<usize> {
+        let mut word = index / WORD_BITS;
+        let mut mask = (1 << (index % WORD_BITS)) - 1;
+        loop {
+            let holes = !self.occupied.get(word).copied().unwrap_or(0) & mask;
+            if holes != 0 {
+                return Some(word * WORD_BITS + (WORD_BITS - 1 - holes.leading_zeros() as usize));
+            }
+            if word == 0 {
+                return None;
+            }
+            word -= 1;
+            mask = u64::MAX;
+        }
+    }
+
+    /// Remove hole from the list of empty elements. It requires walking the list to find the
+    /// previous hole, thus it takes time proportional to the number of holes.
+    fn unlink_hole(&mut self, index: usize) {
+        let next = match self.vec[index] {
+            Cell::Hole(next) => from_link(next),
+            _ => panic!("Unexpected state"),
+        };
+        if self.first_hole == index {
+            self.first_hole = next;
+            return;
+        }
+        if self.reuse == Reuse::LowestIndex {
+            if let Some(Cell::Hole(link)) = self.prev_hole(index).map(|prev| &mut self.vec[prev]) {
+                *link = to_link(next);
+                return;
+            }
+            panic!("Unexpected state");
+        }
+        let mut current = self.first_hole;
+        while current != NO_HOLE {
+            match &mut self.vec[current] {
+                Cell::Hole(link) if from_link(*link) == index => {
+                    *link = to_link(next);
+                    return;
+                },
+                Cell::Hole(link) => current = from_link(*link),
+                _ => panic!("Unexpected state"),
+            }
+        }
+        panic!("Unexpected state");
+    }
+
+    /// Remove empty elements from the end of the vector keeping at least `min_upper_bound`
+    /// elements.
+    fn truncate_holes(&mut self, min_upper_bound: usize) {
+        let upper_bound = self.vec.iter()
+            .rposition(|cell| matches!(cell, Cell::Value(_)))
+            .map_or(0, |index| index + 1)
+            .max(min_upper_bound);
+        if upper_bound >= self.vec.len() {
+            return;
+        }
+        self.unlink_holes_from(upper_bound);
+        self.vec.truncate(upper_bound);
+        self.truncate_occupied(upper_bound);
+    }
+
+    /// Remove holes with indexes greater or equal to `bound` from the list of empty elements.
+    /// The order of the rest of the list is kept.
+    fn unlink_holes_from(&mut self, bound: usize) {
+        self.retain_holes(|index| index < bound);
+    }
+
+    /// Keep in the list of empty elements only holes which indexes satisfy the predicate. The
+    /// order of the rest of the list is kept.
+    fn retain_holes<F: FnMut(usize) -> bool>(&mut self, mut f: F) {
+        let mut prev: Option<usize> = None;
+        let mut current = self.first_hole;
+        while current != NO_HOLE {
+            let next = match self.vec[current] {
+                Cell::Hole(next) => from_link(next),
+                _ => panic!("Unexpected state"),
+            };
+            if f(current) {
+                match prev {
+                    None => self.first_hole = current,
+                    Some(prev) => self.vec[prev] = Cell::Hole(to_link(current)),
+                }
+                prev = Some(current);
+            }
+            current = next;
+        }
+        match prev {
+            None => self.first_hole = NO_HOLE,
+            Some(prev) => self.vec[prev] = Cell::Hole(Idx::MAX),
+        }
+    }
+
+    /// Remove all values from the vector and return them paired with their indexes as an
+    /// iterator in ascending order of indexes. When iterator is dropped the rest of values are
+    /// dropped as well. Vector is empty afterwards, capacity of the underlying vector is kept
+    /// intact. Vector is emptied before the iterator is returned, thus it is left empty even
+    /// when the iterator is leaked. Similar to [std::vec::Vec::drain].
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..5).collect();
+    /// v.remove(1);
+    /// let capacity = v.capacity();
+    ///
+    /// assert_eq!(v.drain().collect::<Vec<_>>(), vec![(0, 0), (2, 2), (3, 3), (4, 4)]);
+    /// assert!(v.is_empty());
+    /// assert_eq!(v.index_upper_bound(), 0);
+    /// assert_eq!(v.next_index(), 0);
+    /// assert_eq!(v.capacity(), capacity);
+    ///
+    /// v.push(1);
+    /// v.push(2);
+    /// v.push(3);
+    /// v.remove(0);
+    /// let mut drain = v.drain();
+    /// assert_eq!(drain.next(), Some((1, 2)));
+    /// drop(drain);
+    /// assert!(v.is_empty());
+    /// assert_eq!(v.push(4), 0);
+    ///
+    /// // Leaked iterator leaves the vector empty
+    /// let rc = std::rc::Rc::new(());
+    /// let mut v: HoleyVec<_> = (0..3).map(|_| rc.clone()).collect();
+    /// v.remove(1);
+    /// std::mem::forget(v.drain());
+    /// assert!(v.is_empty());
+    /// assert_eq!(v.index_upper_bound(), 0);
+    /// assert_eq!(v.push(rc.clone()), 0);
+    /// assert_eq!(v.push(rc.clone()), 1);
+    /// drop(v);
+    /// assert_eq!(std::rc::Rc::strong_count(&rc), 3);
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, T, Idx> {
+        Drain::new(self)
+    }
+
+    /// Remove values with indexes in `range` and return them paired with their indexes as an
+    /// iterator in ascending order of indexes. Removed elements become holes, holes in the range
+    /// are skipped. The part of the range beyond [HoleyVec::index_upper_bound] is ignored. When
+    /// iterator is dropped the rest of values in the range are removed and dropped as well.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..8).collect();
+    /// v.remove(3);
+    /// assert_eq!(v.drain_range(2..5).collect::<Vec<_>>(), vec![(2, 2), (4, 4)]);
+    /// assert_eq!(v.indices().collect::<Vec<usize>>(), vec![0, 1, 5, 6, 7]);
+    ///
+    /// let mut drain = v.drain_range(6..);
+    /// assert_eq!(drain.next(), Some((6, 6)));
+    /// drop(drain);
+    /// assert_eq!(v.indices().collect::<Vec<usize>>(), vec![0, 1, 5]);
+    ///
+    /// assert_eq!(v.drain_range(..=1).count(), 2);
+    /// assert_eq!(v.drain_range(10..20).count(), 0);
+    /// assert_eq!(v.iter_indexed().collect::<Vec<_>>(), vec![(5, &5)]);
+    /// assert_eq!(v.index_upper_bound(), 8);
+    ///
+    /// assert_eq!((0..8).map(|i| v.push(i)).collect::<Vec<usize>>(), vec![1, 0, 7, 6, 4, 2, 3, 8]);
+    /// ```
+    pub fn drain_range<R: core::ops::RangeBounds<usize>>(&mut self, range: R)
+        -> DrainRange<'_, T, Idx> {
+        use core::ops::Bound;
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.saturating_add(1),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.saturating_add(1),
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => usize::MAX,
+        };
+        DrainRange::new(self, start, end)
+    }
+
+    /// Remove all elements with indexes greater or equal to `new_upper_bound`. Empty elements
+    /// below `new_upper_bound` are kept. Do nothing if `new_upper_bound` is greater or equal to
+    /// [HoleyVec::index_upper_bound]. Similar to [std::vec::Vec::truncate].
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..8).collect();
+    /// v.remove(6);
+    /// v.remove(1);
+    /// v.remove(4);
+    /// v.remove(2);
+    /// v.truncate(10);
+    /// assert_eq!(v.index_upper_bound(), 8);
+    ///
+    /// v.truncate(5);
+    /// assert_eq!(v.index_upper_bound(), 5);
+    /// assert_eq!(v.len(), 2);
+    /// assert_eq!(v.iter().copied().collect::<Vec<i32>>(), vec![0, 3]);
+    ///
+    /// assert_eq!(v.push(8), 2);
+    /// assert_eq!(v.push(9), 4);
+    /// assert_eq!(v.push(10), 1);
+    /// assert_eq!(v.push(11), 5);
+    ///
+    /// v.truncate(0);
+    /// assert!(v.is_empty());
+    /// assert_eq!(v.next_index(), 0);
+    /// ```
+    pub fn truncate(&mut self, new_upper_bound: usize) {
+        if new_upper_bound >= self.vec.len() {
+            return;
+        }
+        self.unlink_holes_from(new_upper_bound);
+        self.len -= self.next_generations(new_upper_bound);
+        self.vec.truncate(new_upper_bound);
+        self.truncate_occupied(new_upper_bound);
+    }
+
+    /// Change [HoleyVec::index_upper_bound] to `new_upper_bound`. When the vector grows new
+    /// elements are filled by values returned by `f`, existing holes are kept. When the vector
+    /// shrinks it is truncated as [HoleyVec::truncate] does. Similar to
+    /// [std::vec::Vec::resize_with].
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..5).collect();
+    /// v.remove(1);
+    /// v.remove(4);
+    /// v.remove(3);
+    ///
+    /// let mut next = 10;
+    /// v.resize_with(7, || { next += 1; next });
+    /// assert_eq!(v.index_upper_bound(), 7);
+    /// assert_eq!(v.len(), 4);
+    /// assert_eq!(v.iter_indexed().collect::<Vec<_>>(),
+    ///     vec![(0, &0), (2, &2), (5, &11), (6, &12)]);
+    /// assert_eq!(v.push(30), 3);
+    /// assert_eq!(v.push(40), 4);
+    /// assert_eq!(v.push(10), 1);
+    /// assert_eq!(v.push(70), 7);
+    ///
+    /// v.remove(2);
+    /// v.resize_with(3, || unreachable!());
+    /// assert_eq!(v.indices().collect::<Vec<usize>>(), vec![0, 1]);
+    /// assert_eq!(v.push(20), 2);
+    /// assert_eq!(v.push(30), 3);
+    /// ```
+    #[track_caller]
+    pub fn resize_with<F: FnMut() -> T>(&mut self, new_upper_bound: usize, mut f: F) {
+        if new_upper_bound <= self.vec.len() {
+            self.truncate(new_upper_bound);
+            return;
+        }
+        self.check_new_index(new_upper_bound - 1);
+        self.vec.reserve(new_upper_bound - self.vec.len());
+        for index in self.vec.len()..new_upper_bound {
+            self.vec.push(Cell::Value(f()));
+            self.set_occupied(index);
+            self.len += 1;
+        }
+    }
+
+    /// Move values into indexes `0..len` removing all holes. Order of values is preserved.
+    /// Return a mapping of indexes: element of the result by the previous index of the value
+    /// contains its new index, elements by indexes of holes contain `usize::MAX`. The length of
+    /// the result is equal to the previous [HoleyVec::index_upper_bound]. Keys of moved values
+    /// become invalid.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<char> = "abcdefg".chars().collect();
+    /// v.remove(1);
+    /// v.remove(4);
+    /// v.remove(5);
+    /// let key = v.push_key('x');
+    /// assert_eq!(key.index(), 5);
+    /// let removed = v.push_key('y');
+    /// assert_eq!(removed.index(), 4);
+    /// let stable = v.push_key('z');
+    /// assert_eq!(stable.index(), 1);
+    /// v.remove(6);
+    /// v.remove(4);
+    ///
+    /// assert_eq!(v.compact(), vec![0, 1, 2, 3, usize::MAX, 4, usize::MAX]);
+    /// assert_eq!(v.hole_count(), 0);
+    /// assert_eq!(v.len(), 5);
+    /// assert_eq!(v.index_upper_bound(), 5);
+    /// assert_eq!(v.iter_indexed().collect::<Vec<_>>(),
+    ///     vec![(0, &'a'), (1, &'z'), (2, &'c'), (3, &'d'), (4, &'x')]);
+    /// assert_eq!(v.get_key(&stable), Some(&'z'));
+    /// assert_eq!(v.get_key(&key), None);
+    /// assert_eq!(v.get_key(&removed), None);
+    ///
+    /// assert_eq!(v.push('h'), 5);
+    /// assert!(v.compact().into_iter().eq(0..6));
+    /// assert!(HoleyVec::<i32>::new().compact().is_empty());
+    /// ```
+    pub fn compact(&mut self) -> Vec<usize> {
+        let mut mapping: Vec<usize> = (0..self.vec.len())
+            .map(|index| if self.is_hole(index) { usize::MAX } else { index })
+            .collect();
+        self.compact_with(|old_index, new_index| mapping[old_index] = new_index);
+        mapping
+    }
+
+    /// Move values into indexes `0..len` removing all holes as [HoleyVec::compact] does. Call
+    /// `on_move` with the previous and the new index of each value which changes its index, in
+    /// ascending order of indexes.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<char> = "abcdefgh".chars().collect();
+    /// v.remove(1);
+    /// v.remove(2);
+    /// v.remove(5);
+    /// v.remove(7);
+    ///
+    /// let mut moves = Vec::new();
+    /// v.compact_with(|old_index, new_index| moves.push((old_index, new_index)));
+    /// assert_eq!(moves, vec![(3, 1), (4, 2), (6, 3)]);
+    /// assert_eq!(v.hole_count(), 0);
+    /// assert_eq!(v.iter().collect::<String>(), "adeg");
+    ///
+    /// v.compact_with(|_, _| unreachable!());
+    /// ```
+    pub fn compact_with<F: FnMut(usize, usize)>(&mut self, mut on_move: F) {
+        let occupied = core::mem::take(&mut self.occupied);
+        let mut next = 0;
+        for index in 0..self.vec.len() {
+            if let Cell::Value(_) = self.vec[index] {
+                if index != next {
+                    self.vec.swap(index, next);
+                    self.next_generation(index);
+                }
+                next += 1;
+            }
+        }
+        self.vec.truncate(next);
+        self.first_hole = NO_HOLE;
+        self.fill_occupied();
+        // Vector is consistent at this point even if `on_move` panics
+        for (new_index, old_index) in Occupied::new(&occupied, self.len).enumerate() {
+            if old_index != new_index {
+                on_move(old_index, new_index);
+            }
+        }
+    }
+
+    /// Move all elements with indexes greater or equal to `at` into a new vector. Indexes in
+    /// the returned vector are shifted down by `at`, its holes are reused by [HoleyVec::push]
+    /// in ascending order. The maximal index of the returned vector (see
+    /// [HoleyVec::with_max_index]) is shifted down as well, thus its values cannot go beyond the
+    /// limit of the original vector. Elements below `at` are kept as [HoleyVec::truncate] does.
+    /// Return an empty vector if `at` is greater or equal to [HoleyVec::index_upper_bound].
+    /// Similar to [std::vec::Vec::split_off].
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..8).collect();
+    /// v.remove(1);
+    /// v.remove(5);
+    /// v.remove(4);
+    ///
+    /// let mut w = v.split_off(3);
+    /// assert_eq!(v.indices().collect::<Vec<usize>>(), vec![0, 2]);
+    /// assert_eq!(v.get(2), Some(&2));
+    /// assert_eq!(v.index_upper_bound(), 3);
+    /// assert_eq!(w.indices().collect::<Vec<usize>>(), vec![0, 3, 4]);
+    /// assert_eq!(w.get(0), Some(&3));
+    /// assert_eq!(w.get(4), Some(&7));
+    /// assert_eq!(w.index_upper_bound(), 5);
+    ///
+    /// assert_eq!(v.push(8), 1);
+    /// assert_eq!(v.push(9), 3);
+    /// assert_eq!(w.push(10), 1);
+    /// assert_eq!(w.push(11), 2);
+    /// assert_eq!(w.push(12), 5);
+    ///
+    /// assert!(v.split_off(10).is_empty());
+    /// assert_eq!(v.index_upper_bound(), 4);
+    ///
+    /// // Maximal index is shifted
+    /// let mut v: HoleyVec<i32> = HoleyVec::with_max_index(9);
+    /// v.extend(0..8);
+    /// let mut w = v.split_off(6);
+    /// assert_eq!(w.try_push(8), Ok(2));
+    /// assert_eq!(w.try_push(9), Ok(3));
+    /// assert_eq!(w.try_push(10), Err(10));
+    /// ```
+    pub fn split_off(&mut self, at: usize) -> Self {
+        if at >= self.vec.len() {
+            return Self::default();
+        }
+        self.unlink_holes_from(at);
+        let len = self.next_generations(at);
+        self.len -= len;
+        let mut other = Self{ len, vec: self.vec.split_off(at),
+            max_index: self.max_index.saturating_sub(at), reuse: self.reuse, ..Self::default() };
+        self.truncate_occupied(at);
+        other.link_holes();
+        other.fill_occupied();
+        other
+    }
+
+    /// Move all values of `other` into the vector as [HoleyVec::push] does, `other` becomes
+    /// empty. Return pairs of the previous index in `other` and the new index in the vector for
+    /// each value moved, in ascending order of the previous indexes. Panics when the values
+    /// don't fit into holes and new elements allowed by the maximal index (see
+    /// [HoleyVec::with_max_index] and [Index]), both vectors are not changed in this case.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<char> = ['a', 'b', 'c'].into_iter().collect();
+    /// v.remove(1);
+    /// let mut w: HoleyVec<char> = ['x', 'y', 'z'].into_iter().collect();
+    /// w.remove(0);
+    ///
+    /// assert_eq!(v.append(&mut w), vec![(1, 1), (2, 3)]);
+    /// assert_eq!(v.iter().copied().collect::<String>(), "aycz");
+    /// assert!(w.is_empty());
+    /// assert_eq!(w.index_upper_bound(), 0);
+    ///
+    /// // Bounded vector
+    /// use std::panic::{catch_unwind, AssertUnwindSafe};
+    /// let mut v = HoleyVec::with_max_index(2);
+    /// v.extend(['a', 'b']);
+    /// let mut w: HoleyVec<char> = ['x', 'y'].into_iter().collect();
+    /// let error = catch_unwind(AssertUnwindSafe(|| v.append(&mut w))).unwrap_err();
+    /// assert_eq!(*error.downcast::<String>().unwrap(),
+    ///     "Cannot append 2 values, there is room for 1 values only");
+    /// assert_eq!(v.len(), 2);
+    /// assert_eq!(w.iter().copied().collect::<String>(), "xy");
+    /// v.remove(0);
+    /// assert_eq!(v.append(&mut w), vec![(0, 0), (1, 2)]);
+    /// ```
+    #[track_caller]
+    pub fn append(&mut self, other: &mut Self) -> Vec<(usize, usize)> {
+        let room = self.room();
+        if other.len > room {
+            panic!("Cannot append {} values, there is room for {} values only", other.len, room);
+        }
+        self.reserve(other.len);
+        other.drain().map(|(index, value)| (index, self.push(value))).collect()
+    }
+
+    /// Convert the vector into the vector of optional values keeping indexes of the values.
+    /// Holes including the trailing ones become `None`. The length of the result is equal to
+    /// [HoleyVec::index_upper_bound]. The vector is restored from the result by `HoleyVec::from`
+    /// which keeps indexes of values and holes.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..5).collect();
+    /// v.remove(1);
+    /// v.remove(4);
+    /// assert_eq!(v.clone().into_vec(), vec![Some(0), None, Some(2), Some(3), None]);
+    ///
+    /// let w = HoleyVec::from(v.clone().into_vec());
+    /// assert_eq!(w, v);
+    /// assert_eq!(w.index_upper_bound(), 5);
+    /// assert_eq!(w.iter_indexed().collect::<Vec<_>>(), v.iter_indexed().collect::<Vec<_>>());
+    /// assert!(w.is_hole(1) && w.is_hole(4));
+    /// assert_eq!(w.into_vec(), v.into_vec());
+    /// ```
+    pub fn into_vec(self) -> Vec<Option<T>> {
+        self.vec.into_iter()
+            .map(|cell| match cell {
+                Cell::Value(value) => Some(value),
+                Cell::Hole(_) => None,
+            })
+            .collect()
+    }
+
+    /// Convert the vector into the vector of non-empty values in ascending order of indexes.
+    /// Holes are skipped, thus values don't keep their indexes.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..5).collect();
+    /// v.remove(1);
+    /// v.remove(4);
+    /// let dense = v.into_dense_vec();
+    /// assert_eq!(dense, vec![0, 2, 3]);
+    /// assert_eq!(dense.capacity(), 3);
+    ///
+    /// let v: HoleyVec<i32> = (0..3).collect();
+    /// assert_eq!(v.into_dense_vec(), vec![0, 1, 2]);
+    /// ```
+    pub fn into_dense_vec(self) -> Vec<T> {
+        let mut result = Vec::with_capacity(self.len);
+        result.extend(self);
+        result
+    }
+
+    /// Return iterator over non empty elements of the vector, holes are skipped. Iterator can
+    /// be reversed.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v = HoleyVec::new();
+    /// let mut it = v.iter();
+    /// assert_eq!(it.next(), None);
+    ///
+    /// v.push(1);
+    /// v.push(2);
+    /// v.push(3);
+    /// v.remove(1);
+    /// let mut it = v.iter();
+    /// assert_eq!(it.next(), Some(&1));
+    /// assert_eq!(it.next(), Some(&3));
+    ///
+    /// let mut v: HoleyVec<i32> = (0..200).collect();
+    /// v.retain(|index, _| index % 3 == 0 && index != 63);
+    /// let values: Vec<i32> = v.iter().copied().collect();
+    /// let mut reversed: Vec<i32> = v.iter().rev().copied().collect();
+    /// reversed.reverse();
+    /// assert_eq!(reversed, values);
+    ///
+    /// // Iteration from both ends stops when iterators meet
+    /// let mut it = v.iter();
+    /// let mut values = Vec::new();
+    /// while let Some(&value) = it.next() {
+    ///     values.push(value);
+    ///     if let Some(&value) = it.next_back() {
+    ///         values.push(value);
+    ///     }
+    /// }
+    /// values.sort();
+    /// assert_eq!(values, v.iter().copied().collect::<Vec<i32>>());
+    ///
+    /// // Iterator knows the number of values left
+    /// let mut it = v.iter();
+    /// assert_eq!(it.len(), v.len());
+    /// it.next();
+    /// it.next_back();
+    /// assert_eq!(it.len(), v.len() - 2);
+    /// assert_eq!(it.size_hint(), (v.len() - 2, Some(v.len() - 2)));
+    /// assert_eq!(it.by_ref().count(), v.len() - 2);
+    /// assert_eq!(it.len(), 0);
+    /// assert_eq!(v.indices().len(), v.len());
+    /// assert_eq!(v.clone().into_iter().len(), v.len());
+    /// assert_eq!(v.drain().skip(1).len(), 65);
+    ///
+    /// // Exhausted iterator keeps returning `None`
+    /// let v: HoleyVec<i32> = (0..3).collect();
+    /// let mut it = v.iter();
+    /// assert_eq!(it.by_ref().count(), 3);
+    /// for _ in 0..3 {
+    ///     assert_eq!(it.next(), None);
+    ///     assert_eq!(it.next_back(), None);
+    /// }
+    /// let mut it = v.into_iter();
+    /// assert_eq!(it.by_ref().count(), 3);
+    /// assert_eq!(it.next(), None);
+    /// assert_eq!(it.next(), None);
+    /// ```
+    pub fn iter(&self) -> Iter<'_, T, Idx> {
+        Iter::new(self)
+    }
+
+    /// Return mutable iterator over non empty elements of the vector, holes are skipped.
+    /// 
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v = HoleyVec::new();
+    /// let mut it = v.iter_mut();
+    /// assert_eq!(it.next(), None);
+    ///
+    /// v.push(1);
+    /// v.push(2);
+    /// v.push(3);
+    /// v.remove(1);
+    /// let mut it = v.iter_mut();
+    /// assert_eq!(it.next(), Some(&mut 1));
+    /// assert_eq!(it.next(), Some(&mut 3));
+    ///
+    /// for (value, rank) in v.iter_mut().rev().zip(0..) {
+    ///     *value = rank;
+    /// }
+    /// assert_eq!(v.iter().copied().collect::<Vec<i32>>(), vec![1, 0]);
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, Idx> {
+        IterMut::new(self)
+    }
+
+    /// Return iterator over non empty elements of the vector and their indexes.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v = HoleyVec::new();
+    /// let mut it = v.iter_indexed();
+    /// assert_eq!(it.next(), None);
+    ///
+    /// v.push(1);
+    /// v.push(2);
+    /// v.push(3);
+    /// v.remove(1);
+    /// let mut it = v.iter_indexed();
+    /// assert_eq!(it.next(), Some((0, &1)));
+    /// assert_eq!(it.next(), Some((2, &3)));
+    /// assert_eq!(it.next(), None);
+    /// ```
+    pub fn iter_indexed(&self) -> IndexedIter<'_, T, Idx> {
+        IndexedIter::new(self)
+    }
+
+    /// Return mutable iterator over non empty elements of the vector and their indexes.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v = HoleyVec::new();
+    /// let mut it = v.iter_indexed_mut();
+    /// assert_eq!(it.next(), None);
+    ///
+    /// v.push(1);
+    /// v.push(2);
+    /// v.push(3);
+    /// v.remove(1);
+    /// let mut indexes = Vec::new();
+    /// for (index, value) in v.iter_indexed_mut() {
+    ///     indexes.push(index);
+    ///     *value *= 2;
+    /// }
+    /// assert_eq!(indexes, vec![0, 2]);
+    /// assert_eq!(v.get(0), Some(&2));
+    /// assert_eq!(v.get(2), Some(&6));
+    /// ```
+    pub fn iter_indexed_mut(&mut self) -> IndexedIterMut<'_, T, Idx> {
+        IndexedIterMut::new(self)
+    }
+
+    /// Return iterator over indexes of non empty elements of the vector in ascending order.
+    /// Iterators skip holes by words of the internal occupancy bitset, thus iterating through a
+    /// sparse vector takes time proportional to the number of values plus the index upper bound
+    /// divided by 64.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v = HoleyVec::new();
+    /// assert_eq!(v.indices().next(), None);
+    ///
+    /// v.push(1);
+    /// v.push(2);
+    /// v.push(3);
+    /// v.push(4);
+    /// v.remove(1);
+    /// v.remove(2);
+    /// assert_eq!(v.indices().collect::<Vec<_>>(), vec![0, 3]);
+    ///
+    /// // Sparse vector
+    /// let v = HoleyVec::from_entries([(0, 'a'), (64, 'b'), (4096, 'c')]);
+    /// assert_eq!(v.indices().collect::<Vec<_>>(), vec![0, 64, 4096]);
+    /// ```
+    pub fn indices(&self) -> Indices<'_, T, Idx> {
+        Indices::new(self)
+    }
+
+    /// Return iterator through indexes of holes in the order they are reused by
+    /// [HoleyVec::push]. The iterator follows the list of empty elements and stops after
+    /// [HoleyVec::hole_count] indexes, thus it never loops even if the list is corrupted.
+    /// Yielding an index twice means the list contains a cycle.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..6).collect();
+    /// assert_eq!(v.holes().next(), None);
+    ///
+    /// v.remove(1);
+    /// v.remove(4);
+    /// v.remove(2);
+    /// // Last removed element is reused first
+    /// assert_eq!(v.holes().collect::<Vec<usize>>(), vec![2, 4, 1]);
+    /// assert_eq!(v.holes().len(), v.hole_count());
+    ///
+    /// v.push(2);
+    /// assert_eq!(v.holes().collect::<Vec<usize>>(), vec![4, 1]);
+    ///
+    /// // Holes added by insert are reused first in ascending order
+    /// v.insert(8, 8);
+    /// assert_eq!(v.holes().collect::<Vec<usize>>(), vec![6, 7, 4, 1]);
+    /// ```
+    pub fn holes(&self) -> Holes<'_, T, Idx> {
+        Holes{ vec: &self.vec, next: self.first_hole, remaining: self.hole_count() }
+    }
+
+    /// Return iterator over non empty elements of the vector, holes are skipped. Same as
+    /// [HoleyVec::iter].
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v = HoleyVec::new();
+    /// v.push(1);
+    /// v.push(2);
+    /// v.push(3);
+    /// v.remove(1);
+    /// assert!(v.values().eq(v.iter()));
+    /// assert_eq!(v.values().copied().collect::<Vec<i32>>(), vec![1, 3]);
+    /// ```
+    pub fn values(&self) -> Iter<'_, T, Idx> {
+        self.iter()
+    }
+
+    /// Return mutable iterator over non empty elements of the vector, holes are skipped. Same
+    /// as [HoleyVec::iter_mut].
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v = HoleyVec::new();
+    /// v.push(1);
+    /// v.push(2);
+    /// v.push(3);
+    /// v.remove(1);
+    /// let values: Vec<i32> = v.values_mut().map(|v| *v).collect();
+    /// let iter: Vec<i32> = v.iter_mut().map(|v| *v).collect();
+    /// assert_eq!(values, iter);
+    ///
+    /// v.values_mut().for_each(|v| *v *= 10);
+    /// assert_eq!(v.values().copied().collect::<Vec<i32>>(), vec![10, 30]);
+    /// ```
+    pub fn values_mut(&mut self) -> IterMut<'_, T, Idx> {
+        self.iter_mut()
+    }
+
+    /// Return iterator through all elements of the vector including holes. Holes are returned
+    /// as `None`, thus the position of the element in the iterator is equal to its index. The
+    /// length of the iterator is equal to [HoleyVec::index_upper_bound].
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (1..4).collect();
+    /// v.remove(1);
+    /// assert_eq!(v.iter_with_holes().collect::<Vec<_>>(), vec![Some(&1), None, Some(&3)]);
+    /// assert_eq!(v.iter_with_holes().len(), v.index_upper_bound());
+    /// assert_eq!(v.iter_with_holes().rev().nth(1), Some(None));
+    /// ```
+    pub fn iter_with_holes(&self) -> IterWithHoles<'_, T, Idx> {
+        IterWithHoles{ delegate: self.vec.iter() }
+    }
+}
+
+/// Initialize a new, empty vector (see [HoleyVec::new]).
+///
+/// ## Examples
+/// ```
+/// use holeyvec::HoleyVec;
+///
+/// let v = HoleyVec::<i32>::default();
+/// assert!(v.is_empty());
+/// assert_eq!(v.index_upper_bound(), 0);
+///
+/// #[derive(Default)]
+/// struct Registry {
+///     entries: HoleyVec<String>,
+/// }
+/// assert!(Registry::default().entries.is_empty());
+/// ```
+impl<T, Idx: Index> Default for HoleyVec<T, Idx> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+/// Format non-empty elements as a map of indexes to values. Holes are not shown.
+///
+/// ## Examples
+/// ```
+/// use holeyvec::HoleyVec;
+///
+/// let mut v: HoleyVec<i32> = [42, 1, 2, 7].into_iter().collect();
+/// v.remove(1);
+/// v.remove(2);
+/// assert_eq!(format!("{:?}", v), "{0: 42, 3: 7}");
+/// assert_eq!(format!("{:?}", HoleyVec::<i32>::new()), "{}");
+/// ```
+impl<T: core::fmt::Debug, Idx: Index> core::fmt::Debug for HoleyVec<T, Idx> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.iter_indexed()).finish()
+    }
+}
+
+/// Two vectors are equal when they have equal values at the same indexes. The order in which
+/// holes are reused and the holes at the end of the vectors are not taken into account.
+///
+/// ## Examples
+/// ```
+/// use holeyvec::HoleyVec;
+///
+/// let mut a: HoleyVec<i32> = (0..4).collect();
+/// a.remove(1);
+/// a.remove(2);
+/// a.remove(3);
+///
+/// let mut b: HoleyVec<i32> = (0..3).collect();
+/// b.remove(2);
+/// b.remove(1);
+/// assert_eq!(a, b);
+/// assert_ne!(a.next_index(), b.next_index());
+///
+/// b.push(1);
+/// assert_ne!(a, b);
+///
+/// let c: HoleyVec<i32> = vec![0, 1].into_iter().collect();
+/// let mut d: HoleyVec<i32> = vec![0, 1].into_iter().collect();
+/// d.remove(1);
+/// assert_ne!(c, d);
+/// ```
+impl<T: PartialEq, Idx: Index> PartialEq for HoleyVec<T, Idx> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter_indexed().eq(other.iter_indexed())
+    }
+}
+
+impl<T: Eq, Idx: Index> Eq for HoleyVec<T, Idx> {}
+
+/// Hash is consistent with [PartialEq]: only indexes and values of non-empty elements are
+/// hashed.
+///
+/// ## Examples
+/// ```
+/// use holeyvec::HoleyVec;
+/// use std::collections::hash_map::DefaultHasher;
+/// use std::hash::{Hash, Hasher};
+///
+/// fn hash(v: &HoleyVec<i32>) -> u64 {
+///     let mut hasher = DefaultHasher::new();
+///     v.hash(&mut hasher);
+///     hasher.finish()
+/// }
+///
+/// let mut a: HoleyVec<i32> = (0..4).collect();
+/// a.remove(1);
+/// a.remove(2);
+/// a.remove(3);
+///
+/// let mut b: HoleyVec<i32> = (0..3).collect();
+/// b.remove(2);
+/// b.remove(1);
+/// assert_eq!(a, b);
+/// assert_eq!(hash(&a), hash(&b));
+/// ```
+impl<T: core::hash::Hash, Idx: Index> core::hash::Hash for HoleyVec<T, Idx> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        for entry in self.iter_indexed() {
+            entry.hash(state);
+        }
+    }
+}
+
+/// Get value by index. Panics when element is empty or index is out of bounds.
+///
+/// ## Examples
+/// ```
+/// use holeyvec::HoleyVec;
+/// use std::panic::catch_unwind;
+///
+/// let mut v: HoleyVec<i32> = (0..3).collect();
+/// v.remove(1);
+/// v[2] = 20;
+/// assert_eq!(v[0], 0);
+/// assert_eq!(v[2], 20);
+///
+/// let error = catch_unwind(|| v[1]).unwrap_err();
+/// assert_eq!(*error.downcast::<String>().unwrap(), "Index 1 is a hole, index upper bound is 3");
+/// let error = catch_unwind(|| v[3]).unwrap_err();
+/// assert_eq!(*error.downcast::<String>().unwrap(),
+///     "Index 3 is out of bounds, index upper bound is 3");
+///
+/// let mut w = v.clone();
+/// let error = catch_unwind(move || w[1] = 10).unwrap_err();
+/// assert_eq!(*error.downcast::<String>().unwrap(), "Index 1 is a hole, index upper bound is 3");
+/// ```
+impl<T, Idx: Index> core::ops::Index<usize> for HoleyVec<T, Idx> {
+    type Output = T;
+
+    #[track_caller]
+    fn index(&self, index: usize) -> &Self::Output {
+        match self.get(index) {
+            Some(value) => value,
+            None => self.missing_value(index),
+        }
+    }
+}
+
+impl<T, Idx: Index> core::ops::IndexMut<usize> for HoleyVec<T, Idx> {
+
+    #[track_caller]
+    fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        if !self.contains_index(index) {
+            self.missing_value(index);
+        }
+        match &mut self.vec[index] {
+            Cell::Value(value) => value,
+            Cell::Hole(_) => unreachable!(),
+        }
+    }
+}
+
+/// Element of [HoleyVec] which is either occupied or vacant, see [HoleyVec::entry].
+pub enum Entry<'a, T, Idx = usize> {
+    /// Non-empty element.
+    Occupied(OccupiedEntry<'a, T, Idx>),
+    /// Empty element or element out of bounds.
+    Vacant(VacantEntry<'a, T, Idx>),
+}
+
+impl<'a, T, Idx: Index> Entry<'a, T, Idx> {
+    /// Return index of the element.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..2).collect();
+    /// assert_eq!(v.entry(1).index(), 1);
+    /// assert_eq!(v.entry(5).index(), 5);
+    /// ```
+    pub fn index(&self) -> usize {
+        match self {
+            Entry::Occupied(entry) => entry.index(),
+            Entry::Vacant(entry) => entry.index(),
+        }
+    }
+
+    /// Return mutable value of the element putting `value` into the vacant element.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..2).collect();
+    /// v.remove(0);
+    /// *v.entry(0).or_insert(10) += 1;
+    /// *v.entry(1).or_insert(10) += 1;
+    /// assert_eq!(v.iter().copied().collect::<Vec<i32>>(), vec![11, 2]);
+    /// ```
+    pub fn or_insert(self, value: T) -> &'a mut T {
+        self.or_insert_with(|| value)
+    }
+
+    /// Return mutable value of the element putting the value returned by `f` into the vacant
+    /// element. Function `f` is called only when element is vacant.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<String> = HoleyVec::new();
+    /// v.entry(2).or_insert_with(String::new).push('a');
+    /// v.entry(2).or_insert_with(|| unreachable!()).push('b');
+    /// assert_eq!(v.get(2).map(String::as_str), Some("ab"));
+    /// assert_eq!(v.next_index(), 0);
+    /// ```
+    pub fn or_insert_with<F: FnOnce() -> T>(self, f: F) -> &'a mut T {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(f()),
+        }
+    }
+
+    /// Return mutable value of the element putting the default value into the vacant element.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<u32> = HoleyVec::new();
+    /// for index in [1, 3, 1] {
+    ///     *v.entry(index).or_default() += 1;
+    /// }
+    /// assert_eq!(v.iter_indexed().collect::<Vec<_>>(), vec![(1, &2), (3, &1)]);
+    /// ```
+    pub fn or_default(self) -> &'a mut T where T: Default {
+        self.or_insert_with(T::default)
+    }
+
+    /// Call `f` with the value of the occupied element, vacant element is left untouched.
+    ///
+    /// ## Examples
+    /// ```
+    /// use holeyvec::HoleyVec;
+    ///
+    /// let mut v: HoleyVec<i32> = (0..2).collect();
+    /// v.remove(0);
+    /// v.entry(0).and_modify(|value| *value += 10);
+    /// v.entry(1).and_modify(|value| *value += 10).or_insert(0);
+    /// assert_eq!(v.iter_indexed().collect::<Vec<_>>(), vec![(1, &11)]);
+    /// ```
+    pub fn and_modify<F: FnOnce(&mut T)>(mut self, f: F) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+        self
+    }
+}
+
+/// Non-empty element of [HoleyVec], see [HoleyVec::entry].
+pub struct OccupiedEntry<'a, T, Idx = usize> {
+    vec: &'a mut HoleyVec<T, Idx>,
+    index: usize,
+}
+
+impl<'a, T, Idx: Index> OccupiedEntry<'a, T, Idx> {
+    /// Return index of the element.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Get value of the element.
+    pub fn get(&self) -> &T {
+        &self.vec[self.index]
+    }
+
+    /// Get mutable value of the element.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.vec[self.index]
+    }
+
+    /// Convert entry into mutable value of the element bound to the vector lifetime.
+    pub fn into_mut(self) -> &'a mut T {
+        &mut self.vec[self.index]
+    }
+
+    /// Replace value of the element and return the previous value.
+    pub fn insert(&mut self, value: T) -> T {
+        self.vec.replace(self.index, value)
+    }
+
+    /// Remove value of the element as [HoleyVec::remove] does.
+    pub fn remove(self) -> T {
+        self.vec.remove(self.index)
+    }
+}
+
+/// Empty element of [HoleyVec] or element out of bounds, see [HoleyVec::entry] and
+/// [HoleyVec::vacant_entry].
+pub struct VacantEntry<'a, T, Idx = usize> {
+    vec: &'a mut HoleyVec<T, Idx>,
+    index: usize,
+}
+
+impl<'a, T, Idx: Index> VacantEntry<'a, T, Idx> {
+    /// Return index of the element.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Put value into the element as [HoleyVec::insert] does and return mutable value.
+    #[track_caller]
+    pub fn insert(self, value: T) -> &'a mut T {
+        self.vec.insert(self.index, value);
+        &mut self.vec[self.index]
+    }
+}
+
+/// Iterator through indexes of non-empty elements which jumps over holes using the occupancy
+/// bitset, thus it takes time proportional to the number of values rather than to the index
+/// upper bound.
+#[derive(Clone)]
+struct Occupied<'a> {
+    words: core::slice::Iter<'a, u64>,
+    /// Number of indexes left.
+    remaining: usize,
+    /// Index of the first element of the next word taken from the front.
+    taken: usize,
+    front: u64,
+    front_base: usize,
+    back: u64,
+    back_base: usize,
+}
+
+impl<'a> Occupied<'a> {
+    fn new(occupied: &'a [u64], len: usize) -> Self {
+        Self{ words: occupied.iter(), remaining: len, taken: 0, front: 0, front_base: 0, back: 0,
+            back_base: 0 }
+    }
+}
+
+impl<'a> core::iter::Iterator for Occupied<'a> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.front == 0 {
+            match self.words.next() {
+                Some(&word) => {
+                    self.front = word;
+                    self.front_base = self.taken;
+                    self.taken += WORD_BITS;
+                },
+                None => {
+                    // Continue with the word taken from the back
+                    if self.back == 0 {
+                        return None;
+                    }
+                    let bit = self.back.trailing_zeros() as usize;
+                    self.back &= self.back - 1;
+                    self.remaining -= 1;
+                    return Some(self.back_base + bit);
+                },
+            }
+        }
+        let bit = self.front.trailing_zeros() as usize;
+        self.front &= self.front - 1;
+        self.remaining -= 1;
+        Some(self.front_base + bit)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a> core::iter::DoubleEndedIterator for Occupied<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while self.back == 0 {
+            match self.words.next_back() {
+                Some(&word) => {
+                    self.back = word;
+                    self.back_base = self.taken + self.words.len() * WORD_BITS;
+                },
+                None => {
+                    // Continue with the word taken from the front
+                    if self.front == 0 {
+                        return None;
+                    }
+                    let bit = WORD_BITS - 1 - self.front.leading_zeros() as usize;
+                    self.front &= !(1 << bit);
+                    self.remaining -= 1;
+                    return Some(self.front_base + bit);
+                },
+            }
+        }
+        let bit = WORD_BITS - 1 - self.back.leading_zeros() as usize;
+        self.back &= !(1 << bit);
+        self.remaining -= 1;
+        Some(self.back_base + bit)
+    }
+}
+
+/// Iterator through non-empty elements of the underlying vector paired with indexes, see
+/// [Occupied].
+#[derive(Clone)]
+struct OccupiedCells<'a, C> {
+    cells: C,
+    /// Index of the next element of `cells` from the front.
+    front: usize,
+    /// Index after the next element of `cells` from the back.
+    back: usize,
+    occupied: Occupied<'a>,
+}
+
+impl<'a, C: ExactSizeIterator> OccupiedCells<'a, C> {
+    fn new(cells: C, occupied: &'a [u64], len: usize) -> Self {
+        let back = cells.len();
+        Self{ cells, front: 0, back, occupied: Occupied::new(occupied, len) }
+    }
+}
+
+impl<'a, C: core::iter::Iterator> core::iter::Iterator for OccupiedCells<'a, C> {
+    type Item = (usize, C::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.occupied.next()?;
+        let cell = self.cells.nth(index - self.front)?;
+        self.front = index + 1;
+        Some((index, cell))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.occupied.size_hint()
+    }
+}
+
+impl<'a, C: DoubleEndedIterator> DoubleEndedIterator for OccupiedCells<'a, C> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.occupied.next_back()?;
+        let cell = self.cells.nth_back(self.back - index - 1)?;
+        self.back = index;
+        Some((index, cell))
+    }
+}
+
+/// [HoleyVec] iterator to iterate through non-empty elements of the vector.
+pub struct Iter<'a, T, Idx = usize> {
+    delegate: OccupiedCells<'a, core::slice::Iter<'a, Cell<T, Idx>>>
+}
+
+impl<'a, T, Idx: Index> Iter<'a, T, Idx> {
+    fn new(vec: &'a HoleyVec<T, Idx>) -> Self {
+        Self{ delegate: OccupiedCells::new(vec.vec.iter(), &vec.occupied, vec.len) }
+    }
+}
+
+/// Cloned iterator continues independently from the same position.
+///
+/// ## Examples
+/// ```
+/// use holeyvec::HoleyVec;
+///
+/// let mut v: HoleyVec<i32> = (0..6).collect();
+/// v.remove(1);
+/// v.remove(4);
+///
+/// let mut it = v.iter();
+/// assert_eq!(it.next(), Some(&0));
+/// let mut copy = it.clone();
+/// assert_eq!(it.next(), Some(&2));
+/// assert_eq!(it.next_back(), Some(&5));
+/// assert_eq!(copy.len(), 3);
+/// assert_eq!(copy.by_ref().copied().collect::<Vec<i32>>(), vec![2, 3, 5]);
+/// assert_eq!(it.copied().collect::<Vec<i32>>(), vec![3]);
+/// ```
+impl<'a, T, Idx> Clone for Iter<'a, T, Idx> {
+    fn clone(&self) -> Self {
+        Self{ delegate: self.delegate.clone() }
     }
 }
 
-impl<'a, T> std::iter::Iterator for Iter<'a, T> {
+impl<'a, T, Idx: Index> core::iter::Iterator for Iter<'a, T, Idx> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            match self.delegate.next() {
-                None => return None,
-                Some(Cell::Hole(_)) => continue,
-                Some(Cell::Value(value)) => return Some(value),
-            }
+        match self.delegate.next()? {
+            (_, Cell::Value(value)) => Some(value),
+            (_, Cell::Hole(_)) => unreachable!(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.delegate.size_hint()
+    }
+}
+
+impl<'a, T, Idx: Index> core::iter::ExactSizeIterator for Iter<'a, T, Idx> {}
+
+impl<'a, T, Idx: Index> core::iter::FusedIterator for Iter<'a, T, Idx> {}
+
+impl<'a, T, Idx: Index> core::iter::DoubleEndedIterator for Iter<'a, T, Idx> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self.delegate.next_back()? {
+            (_, Cell::Value(value)) => Some(value),
+            (_, Cell::Hole(_)) => unreachable!(),
         }
     }
 }
 
-impl<'a, T> IntoIterator for &'a HoleyVec<T> {
+impl<'a, T, Idx: Index> IntoIterator for &'a HoleyVec<T, Idx> {
     type Item = &'a T;
-    type IntoIter = Iter<'a, T>;
+    type IntoIter = Iter<'a, T, Idx>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.iter()
@@ -310,42 +3786,743 @@ impl<'a, T> IntoIterator for &'a HoleyVec<T> {
 }
 
 /// [HoleyVec] iterator to iterate through non-empty elements of the vector.
-pub struct IterMut<'a, T> {
-    delegate: std::slice::IterMut<'a, Cell<T>>
+pub struct IterMut<'a, T, Idx = usize> {
+    delegate: OccupiedCells<'a, core::slice::IterMut<'a, Cell<T, Idx>>>
+}
+
+impl<'a, T, Idx: Index> IterMut<'a, T, Idx> {
+    fn new(vec: &'a mut HoleyVec<T, Idx>) -> Self {
+        Self{ delegate: OccupiedCells::new(vec.vec.iter_mut(), &vec.occupied, vec.len) }
+    }
+}
+
+impl<'a, T, Idx: Index> core::iter::Iterator for IterMut<'a, T, Idx> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.delegate.next()? {
+            (_, Cell::Value(value)) => Some(value),
+            (_, Cell::Hole(_)) => unreachable!(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.delegate.size_hint()
+    }
 }
 
-impl<'a, T> IterMut<'a, T> {
-    fn new(vec: &'a mut HoleyVec<T>) -> Self {
-        Self{ delegate: vec.vec.iter_mut() }
+impl<'a, T, Idx: Index> core::iter::ExactSizeIterator for IterMut<'a, T, Idx> {}
+
+impl<'a, T, Idx: Index> core::iter::FusedIterator for IterMut<'a, T, Idx> {}
+
+impl<'a, T, Idx: Index> core::iter::DoubleEndedIterator for IterMut<'a, T, Idx> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self.delegate.next_back()? {
+            (_, Cell::Value(value)) => Some(value),
+            (_, Cell::Hole(_)) => unreachable!(),
+        }
     }
 }
 
-impl<'a, T> std::iter::Iterator for IterMut<'a, T> {
+impl<'a, T, Idx: Index> IntoIterator for &'a mut HoleyVec<T, Idx> {
     type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T, Idx>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// [HoleyVec] iterator to iterate through non-empty elements of the vector and their indexes.
+pub struct IndexedIter<'a, T, Idx = usize> {
+    delegate: OccupiedCells<'a, core::slice::Iter<'a, Cell<T, Idx>>>
+}
+
+impl<'a, T, Idx: Index> IndexedIter<'a, T, Idx> {
+    fn new(vec: &'a HoleyVec<T, Idx>) -> Self {
+        Self{ delegate: OccupiedCells::new(vec.vec.iter(), &vec.occupied, vec.len) }
+    }
+}
+
+impl<'a, T, Idx: Index> core::iter::Iterator for IndexedIter<'a, T, Idx> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.delegate.next()? {
+            (index, Cell::Value(value)) => Some((index, value)),
+            (_, Cell::Hole(_)) => unreachable!(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.delegate.size_hint()
+    }
+}
+
+impl<'a, T, Idx: Index> core::iter::ExactSizeIterator for IndexedIter<'a, T, Idx> {}
+
+impl<'a, T, Idx: Index> core::iter::FusedIterator for IndexedIter<'a, T, Idx> {}
+
+impl<'a, T, Idx: Index> core::iter::DoubleEndedIterator for IndexedIter<'a, T, Idx> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self.delegate.next_back()? {
+            (index, Cell::Value(value)) => Some((index, value)),
+            (_, Cell::Hole(_)) => unreachable!(),
+        }
+    }
+}
+
+/// [HoleyVec] iterator to iterate through non-empty elements of the vector and their indexes.
+pub struct IndexedIterMut<'a, T, Idx = usize> {
+    delegate: OccupiedCells<'a, core::slice::IterMut<'a, Cell<T, Idx>>>
+}
+
+impl<'a, T, Idx: Index> IndexedIterMut<'a, T, Idx> {
+    fn new(vec: &'a mut HoleyVec<T, Idx>) -> Self {
+        Self{ delegate: OccupiedCells::new(vec.vec.iter_mut(), &vec.occupied, vec.len) }
+    }
+}
+
+impl<'a, T, Idx: Index> core::iter::Iterator for IndexedIterMut<'a, T, Idx> {
+    type Item = (usize, &'a mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.delegate.next()? {
+            (index, Cell::Value(value)) => Some((index, value)),
+            (_, Cell::Hole(_)) => unreachable!(),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.delegate.size_hint()
+    }
+}
+
+impl<'a, T, Idx: Index> core::iter::ExactSizeIterator for IndexedIterMut<'a, T, Idx> {}
+
+impl<'a, T, Idx: Index> core::iter::FusedIterator for IndexedIterMut<'a, T, Idx> {}
+
+impl<'a, T, Idx: Index> core::iter::DoubleEndedIterator for IndexedIterMut<'a, T, Idx> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self.delegate.next_back()? {
+            (index, Cell::Value(value)) => Some((index, value)),
+            (_, Cell::Hole(_)) => unreachable!(),
+        }
+    }
+}
+
+/// [HoleyVec] iterator to iterate through indexes of non-empty elements of the vector.
+pub struct Indices<'a, T, Idx = usize> {
+    delegate: Occupied<'a>,
+    vec: core::marker::PhantomData<&'a HoleyVec<T, Idx>>,
+}
+
+impl<'a, T, Idx: Index> Indices<'a, T, Idx> {
+    fn new(vec: &'a HoleyVec<T, Idx>) -> Self {
+        Self{ delegate: Occupied::new(&vec.occupied, vec.len), vec: core::marker::PhantomData }
+    }
+}
+
+impl<'a, T, Idx: Index> core::iter::Iterator for Indices<'a, T, Idx> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.delegate.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.delegate.size_hint()
+    }
+}
+
+impl<'a, T, Idx: Index> core::iter::ExactSizeIterator for Indices<'a, T, Idx> {}
+
+impl<'a, T, Idx: Index> core::iter::FusedIterator for Indices<'a, T, Idx> {}
+
+impl<'a, T, Idx: Index> core::iter::DoubleEndedIterator for Indices<'a, T, Idx> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.delegate.next_back()
+    }
+}
+
+/// [HoleyVec] iterator through all elements including holes, see [HoleyVec::iter_with_holes].
+pub struct IterWithHoles<'a, T, Idx = usize> {
+    delegate: core::slice::Iter<'a, Cell<T, Idx>>,
+}
+
+fn cell_value<T, Idx>(cell: &Cell<T, Idx>) -> Option<&T> {
+    match cell {
+        Cell::Value(value) => Some(value),
+        Cell::Hole(_) => None,
+    }
+}
+
+impl<'a, T, Idx: Index> core::iter::Iterator for IterWithHoles<'a, T, Idx> {
+    type Item = Option<&'a T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.delegate.next().map(cell_value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.delegate.size_hint()
+    }
+}
+
+impl<'a, T, Idx: Index> core::iter::ExactSizeIterator for IterWithHoles<'a, T, Idx> {}
+
+impl<'a, T, Idx: Index> core::iter::FusedIterator for IterWithHoles<'a, T, Idx> {}
+
+impl<'a, T, Idx: Index> core::iter::DoubleEndedIterator for IterWithHoles<'a, T, Idx> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.delegate.next_back().map(cell_value)
+    }
+}
+
+/// [HoleyVec] iterator to iterate through indexes of empty elements of the vector, see
+/// [HoleyVec::holes].
+pub struct Holes<'a, T, Idx = usize> {
+    vec: &'a [Cell<T, Idx>],
+    next: usize,
+    remaining: usize,
+}
+
+impl<'a, T, Idx: Index> core::iter::Iterator for Holes<'a, T, Idx> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 || self.next == NO_HOLE {
+            return None;
+        }
+        let index = self.next;
+        self.next = match self.vec[index] {
+            Cell::Hole(next) => from_link(next),
+            _ => panic!("Unexpected state"),
+        };
+        self.remaining -= 1;
+        Some(index)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T, Idx: Index> core::iter::ExactSizeIterator for Holes<'a, T, Idx> {}
+
+impl<'a, T, Idx: Index> core::iter::FusedIterator for Holes<'a, T, Idx> {}
+
+/// [HoleyVec] iterator to move non-empty elements out of the vector, see [HoleyVec::drain].
+pub struct Drain<'a, T, Idx = usize> {
+    delegate: core::iter::Enumerate<alloc::vec::Drain<'a, Cell<T, Idx>>>,
+    remaining: usize,
+}
+
+impl<'a, T, Idx: Index> Drain<'a, T, Idx> {
+    fn new(vec: &'a mut HoleyVec<T, Idx>) -> Self {
+        let remaining = vec.len;
+        vec.next_generations(0);
+        vec.first_hole = NO_HOLE;
+        vec.len = 0;
+        vec.occupied.clear();
+        Self{ delegate: vec.vec.drain(..).enumerate(), remaining }
+    }
+}
+
+impl<'a, T, Idx: Index> core::iter::Iterator for Drain<'a, T, Idx> {
+    type Item = (usize, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.delegate.next() {
+                None => return None,
+                Some((_, Cell::Hole(_))) => continue,
+                Some((index, Cell::Value(value))) => {
+                    self.remaining -= 1;
+                    return Some((index, value));
+                },
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T, Idx: Index> core::iter::ExactSizeIterator for Drain<'a, T, Idx> {}
+
+impl<'a, T, Idx: Index> core::iter::FusedIterator for Drain<'a, T, Idx> {}
+
+/// [HoleyVec] iterator to move values in range of indexes out of the vector, see
+/// [HoleyVec::drain_range].
+pub struct DrainRange<'a, T, Idx: Index = usize> {
+    vec: &'a mut HoleyVec<T, Idx>,
+    index: usize,
+    end: usize,
+}
+
+impl<'a, T, Idx: Index> DrainRange<'a, T, Idx> {
+    fn new(vec: &'a mut HoleyVec<T, Idx>, start: usize, end: usize) -> Self {
+        let end = end.min(vec.vec.len());
+        Self{ vec, index: start, end }
+    }
+}
+
+impl<'a, T, Idx: Index> core::iter::Iterator for DrainRange<'a, T, Idx> {
+    type Item = (usize, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.end {
+            let index = self.index;
+            self.index += 1;
+            if let Some(value) = self.vec.try_remove(index) {
+                return Some((index, value));
+            }
+        }
+        None
+    }
+}
+
+impl<'a, T, Idx: Index> core::iter::FusedIterator for DrainRange<'a, T, Idx> {}
+
+impl<'a, T, Idx: Index> Drop for DrainRange<'a, T, Idx> {
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+/// [HoleyVec] iterator to move elements matching predicate out of the vector, see
+/// [HoleyVec::extract_if].
+pub struct ExtractIf<'a, T, F, Idx = usize> {
+    vec: &'a mut HoleyVec<T, Idx>,
+    index: usize,
+    filter: F,
+}
+
+impl<'a, T, F, Idx: Index> ExtractIf<'a, T, F, Idx> {
+    fn new(vec: &'a mut HoleyVec<T, Idx>, filter: F) -> Self {
+        Self{ vec, index: 0, filter }
+    }
+}
+
+impl<'a, T, F: FnMut(usize, &mut T) -> bool, Idx: Index> core::iter::Iterator
+    for ExtractIf<'a, T, F, Idx> {
+    type Item = (usize, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.vec.vec.len() {
+            let index = self.index;
+            self.index += 1;
+            if let Cell::Value(value) = &mut self.vec.vec[index] {
+                if (self.filter)(index, value) {
+                    return self.vec.try_remove(index).map(|value| (index, value));
+                }
+            }
+        }
+        None
+    }
+}
+
+impl<'a, T, F: FnMut(usize, &mut T) -> bool, Idx: Index> core::iter::FusedIterator
+    for ExtractIf<'a, T, F, Idx> {}
+
+/// [HoleyVec] iterator to move non-empty elements out of the vector.
+pub struct IntoIter<T, Idx = usize> {
+    delegate: alloc::vec::IntoIter<Cell<T, Idx>>,
+    remaining: usize,
+}
+
+impl<T, Idx: Index> IntoIter<T, Idx> {
+    fn new(vec: HoleyVec<T, Idx>) -> Self {
+        Self{ delegate: vec.vec.into_iter(), remaining: vec.len }
+    }
+}
+
+impl<T, Idx: Index> core::iter::Iterator for IntoIter<T, Idx> {
+    type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
         loop {
             match self.delegate.next() {
                 None => return None,
                 Some(Cell::Hole(_)) => continue,
-                Some(Cell::Value(value)) => return Some(value),
+                Some(Cell::Value(value)) => {
+                    self.remaining -= 1;
+                    return Some(value);
+                },
             }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
 }
 
-impl<'a, T> IntoIterator for &'a mut HoleyVec<T> {
-    type Item = &'a mut T;
-    type IntoIter = IterMut<'a, T>;
+impl<T, Idx: Index> core::iter::ExactSizeIterator for IntoIter<T, Idx> {}
+
+impl<T, Idx: Index> core::iter::FusedIterator for IntoIter<T, Idx> {}
+
+/// Move non-empty elements out of the vector.
+///
+/// ## Examples
+/// ```
+/// use holeyvec::HoleyVec;
+///
+/// let mut v = HoleyVec::new();
+/// v.push(1);
+/// v.push(2);
+/// v.push(3);
+/// v.remove(1);
+/// assert_eq!(v.into_iter().collect::<Vec<i32>>(), vec![1, 3]);
+/// ```
+impl<T, Idx: Index> IntoIterator for HoleyVec<T, Idx> {
+    type Item = T;
+    type IntoIter = IntoIter<T, Idx>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.iter_mut()
+        IntoIter::new(self)
     }
 }
 
-impl<T> FromIterator<T> for HoleyVec<T> {
+/// Collect values into [HoleyVec] without holes. Item `k` of the iterator gets index `k`.
+///
+/// ## Examples
+/// ```
+/// use holeyvec::HoleyVec;
+///
+/// let v: HoleyVec<i32> = (0..5).collect();
+/// assert_eq!(v.len(), 5);
+/// assert_eq!(v.index_upper_bound(), 5);
+/// assert_eq!(v.next_index(), 5);
+/// assert_eq!(v.get(0), Some(&0));
+/// assert_eq!(v.get(4), Some(&4));
+/// assert_eq!(v.get(5), None);
+///
+/// let mut v: HoleyVec<String> = ["a", "b", "c"].iter().map(|s| s.to_uppercase()).collect();
+/// v.remove(1);
+/// assert_eq!(v.iter_indexed().collect::<Vec<_>>(),
+///     vec![(0, &"A".to_string()), (2, &"C".to_string())]);
+/// assert_eq!(v.push("D".to_string()), 1);
+///
+/// let v: HoleyVec<i32> = std::iter::empty::<i32>().collect();
+/// assert!(v.is_empty());
+/// assert_eq!(v.next_index(), 0);
+/// assert_eq!(v.index_upper_bound(), 0);
+/// ```
+impl<T, Idx: Index> FromIterator<T> for HoleyVec<T, Idx> {
     fn from_iter<I: IntoIterator<Item=T>>(iter: I) -> Self {
-        let vec: Vec<Cell<T>> = iter.into_iter().map(Cell::Value).collect();
-        Self { first_hole: vec.len(), vec }
+        let vec: Vec<Cell<T, Idx>> = iter.into_iter().map(Cell::Value).collect();
+        Self::check_upper_bound(vec.len());
+        let mut result = Self { len: vec.len(), vec, ..Self::default() };
+        result.fill_occupied();
+        result
+    }
+}
+
+/// Collect values paired with indexes into [HoleyVec], see [HoleyVec::from_entries].
+///
+/// ## Examples
+/// ```
+/// use holeyvec::HoleyVec;
+///
+/// let v: HoleyVec<char> = vec![(2, 'c'), (0, 'a')].into_iter().collect();
+/// assert_eq!(v.get(0), Some(&'a'));
+/// assert!(v.is_hole(1));
+/// assert_eq!(v.get(2), Some(&'c'));
+/// assert_eq!(v.next_index(), 1);
+/// ```
+impl<T, Idx: Index> FromIterator<(usize, T)> for HoleyVec<T, Idx> {
+    fn from_iter<I: IntoIterator<Item=(usize, T)>>(iter: I) -> Self {
+        Self::collect_entries(iter)
+    }
+}
+
+/// Push values into [HoleyVec]. Empty elements are filled first as [HoleyVec::push] does,
+/// then values are added to the end of the vector.
+///
+/// ## Examples
+/// ```
+/// use holeyvec::HoleyVec;
+///
+/// let mut v: HoleyVec<i32> = (0..4).collect();
+/// v.remove(1);
+/// v.remove(2);
+///
+/// v.extend([4, 5, 6]);
+/// assert_eq!(v.hole_count(), 0);
+/// assert_eq!(v.get(2), Some(&4));
+/// assert_eq!(v.get(1), Some(&5));
+/// assert_eq!(v.get(4), Some(&6));
+/// ```
+impl<T, Idx: Index> Extend<T> for HoleyVec<T, Idx> {
+    fn extend<I: IntoIterator<Item=T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+/// Push copies of values into [HoleyVec]. Empty elements are filled first as [HoleyVec::push]
+/// does, then values are added to the end of the vector.
+///
+/// ## Examples
+/// ```
+/// use holeyvec::HoleyVec;
+///
+/// let mut v = HoleyVec::new();
+/// v.extend(&[1, 2, 3]);
+/// v.remove(0);
+/// v.extend([4, 5].iter());
+/// assert_eq!(v.iter().copied().collect::<Vec<i32>>(), vec![4, 2, 3, 5]);
+/// ```
+impl<'a, T: Copy + 'a, Idx: Index> Extend<&'a T> for HoleyVec<T, Idx> {
+    fn extend<I: IntoIterator<Item=&'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+/// Put values into [HoleyVec] by the indexes they are paired with. Values which are already
+/// in the vector are replaced. When the index is out of bounds the vector is extended by holes.
+/// Filling a hole requires walking the list of empty elements, thus it takes time proportional
+/// to the number of holes.
+///
+/// ## Examples
+/// ```
+/// use holeyvec::HoleyVec;
+///
+/// let mut v: HoleyVec<i32> = (0..6).collect();
+/// v.remove(1);
+/// v.remove(3);
+/// v.remove(4);
+/// assert_eq!(v.next_index(), 4);
+///
+/// v.extend([(3, 30), (0, 10), (8, 80)]);
+/// assert_eq!(v.get(0), Some(&10));
+/// assert_eq!(v.get(3), Some(&30));
+/// assert_eq!(v.get(8), Some(&80));
+/// assert_eq!(v.len(), 5);
+/// assert_eq!(v.index_upper_bound(), 9);
+///
+/// assert_eq!(v.push(6), 6);
+/// assert_eq!(v.push(7), 7);
+/// assert_eq!(v.push(4), 4);
+/// assert_eq!(v.push(1), 1);
+/// assert_eq!(v.push(9), 9);
+/// ```
+impl<T, Idx: Index> Extend<(usize, T)> for HoleyVec<T, Idx> {
+    fn extend<I: IntoIterator<Item=(usize, T)>>(&mut self, iter: I) {
+        for (index, value) in iter {
+            self.insert(index, value);
+        }
+    }
+}
+
+/// Convert vector into [HoleyVec] without holes. Each element keeps its index.
+///
+/// ## Examples
+/// ```
+/// use holeyvec::HoleyVec;
+///
+/// let mut v = HoleyVec::from(vec![1, 2, 3]);
+/// assert_eq!(v.len(), 3);
+/// assert_eq!(v.get(0), Some(&1));
+/// assert_eq!(v.get(2), Some(&3));
+/// assert_eq!(v.next_index(), 3);
+///
+/// v.remove(1);
+/// assert_eq!(v.push(4), 1);
+/// assert_eq!(v.push(5), 3);
+///
+/// let v = HoleyVec::from(Vec::<i32>::new());
+/// assert!(v.is_empty());
+/// assert_eq!(v.next_index(), 0);
+/// assert_eq!(v.index_upper_bound(), 0);
+/// ```
+impl<T> From<Vec<T>> for HoleyVec<T> {
+    fn from(vec: Vec<T>) -> Self {
+        vec.into_iter().collect()
+    }
+}
+
+/// Convert [HoleyVec] into vector when all values keep their indexes, i.e. there are no holes
+/// except trailing ones. Otherwise [HoleyVec] is returned back as an error.
+///
+/// ## Examples
+/// ```
+/// use holeyvec::HoleyVec;
+///
+/// let mut v: HoleyVec<i32> = (0..4).collect();
+/// v.remove(3);
+/// assert_eq!(Vec::try_from(v), Ok(vec![0, 1, 2]));
+///
+/// let mut v: HoleyVec<i32> = (0..4).collect();
+/// v.remove(1);
+/// let v = Vec::try_from(v).unwrap_err();
+/// assert_eq!(v.len(), 3);
+/// ```
+impl<T, Idx: Index> TryFrom<HoleyVec<T, Idx>> for Vec<T> {
+    type Error = HoleyVec<T, Idx>;
+
+    fn try_from(vec: HoleyVec<T, Idx>) -> Result<Self, Self::Error> {
+        if vec.vec[..vec.len].iter().all(|cell| matches!(cell, Cell::Value(_))) {
+            Ok(vec.into_dense_vec())
+        } else {
+            Err(vec)
+        }
+    }
+}
+
+/// Convert [HoleyVec] into map of indexes to non-empty values.
+///
+/// ## Examples
+/// ```
+/// use holeyvec::HoleyVec;
+/// use std::collections::HashMap;
+///
+/// let mut v: HoleyVec<i32> = (0..4).collect();
+/// v.remove(1);
+/// let map = HashMap::from(v);
+/// assert_eq!(map, HashMap::from([(0, 0), (2, 2), (3, 3)]));
+/// ```
+#[cfg(feature = "std")]
+impl<T, Idx: Index, S: core::hash::BuildHasher + Default> From<HoleyVec<T, Idx>>
+    for std::collections::HashMap<usize, T, S> {
+    fn from(vec: HoleyVec<T, Idx>) -> Self {
+        vec.vec.into_iter()
+            .enumerate()
+            .filter_map(|(index, cell)| match cell {
+                Cell::Value(value) => Some((index, value)),
+                Cell::Hole(_) => None,
+            })
+            .collect()
+    }
+}
+
+/// Convert map of indexes to values into [HoleyVec], see [HoleyVec::from_entries]. Indexes
+/// which are missing in the map become holes. Beware that the memory used is proportional to
+/// the maximal index, not to the number of values.
+///
+/// ## Examples
+/// ```
+/// use holeyvec::HoleyVec;
+/// use std::collections::HashMap;
+///
+/// let map = HashMap::from([(1, 'b'), (4, 'e')]);
+/// let mut v = HoleyVec::from(map.clone());
+/// assert_eq!(v.index_upper_bound(), 5);
+/// for index in 0..v.index_upper_bound() {
+///     assert_eq!(v.get(index), map.get(&index));
+/// }
+/// assert_eq!(HashMap::from(v.clone()), map);
+///
+/// assert_eq!(v.push('a'), 0);
+/// assert_eq!(v.push('c'), 2);
+/// assert_eq!(v.push('d'), 3);
+/// assert_eq!(v.push('f'), 5);
+/// ```
+#[cfg(feature = "std")]
+impl<T, S> From<std::collections::HashMap<usize, T, S>> for HoleyVec<T> {
+    fn from(map: std::collections::HashMap<usize, T, S>) -> Self {
+        Self::from_entries(map)
+    }
+}
+
+/// Convert [HoleyVec] into ordered map of indexes to non-empty values.
+///
+/// ## Examples
+/// ```
+/// use holeyvec::HoleyVec;
+/// use std::collections::BTreeMap;
+///
+/// let mut v: HoleyVec<i32> = (0..4).collect();
+/// v.remove(1);
+/// let map = BTreeMap::from(v);
+/// assert_eq!(map.into_iter().collect::<Vec<_>>(), vec![(0, 0), (2, 2), (3, 3)]);
+/// ```
+impl<T, Idx: Index> From<HoleyVec<T, Idx>> for alloc::collections::BTreeMap<usize, T> {
+    fn from(vec: HoleyVec<T, Idx>) -> Self {
+        vec.vec.into_iter()
+            .enumerate()
+            .filter_map(|(index, cell)| match cell {
+                Cell::Value(value) => Some((index, value)),
+                Cell::Hole(_) => None,
+            })
+            .collect()
+    }
+}
+
+/// Convert ordered map of indexes to values into [HoleyVec], see [HoleyVec::from_entries].
+/// Indexes which are missing in the map become holes which are reused by [HoleyVec::push] in
+/// ascending order. Beware that the memory used is proportional to the maximal index, not to
+/// the number of values.
+///
+/// ## Examples
+/// ```
+/// use holeyvec::HoleyVec;
+/// use std::collections::BTreeMap;
+///
+/// let map = BTreeMap::from([(1, 1), (5, 5), (6, 6), (100, 100)]);
+/// let mut v = HoleyVec::from(map.clone());
+/// assert_eq!(v.len(), 4);
+/// assert_eq!(v.index_upper_bound(), 101);
+/// for index in 0..v.index_upper_bound() {
+///     assert_eq!(v.get(index), map.get(&index));
+/// }
+/// assert_eq!(BTreeMap::from(v.clone()), map);
+///
+/// assert_eq!(v.next_index(), 0);
+/// assert_eq!(v.push(0), 0);
+/// assert_eq!(v.push(2), 2);
+/// assert_eq!(v.push(3), 3);
+/// assert_eq!(v.push(4), 4);
+/// assert_eq!(v.push(7), 7);
+/// ```
+impl<T> From<alloc::collections::BTreeMap<usize, T>> for HoleyVec<T> {
+    fn from(map: alloc::collections::BTreeMap<usize, T>) -> Self {
+        Self::from_entries(map)
+    }
+}
+
+/// Convert vector of optional values into [HoleyVec]. Each value keeps its index, `None`
+/// elements become holes including the trailing ones. Holes are reused by [HoleyVec::push] in
+/// ascending order of indexes.
+///
+/// ## Examples
+/// ```
+/// use holeyvec::HoleyVec;
+///
+/// let mut v = HoleyVec::from(vec![None, Some(1), None, Some(2), None]);
+/// assert_eq!(v.len(), 2);
+/// assert_eq!(v.index_upper_bound(), 5);
+/// assert_eq!(v.get(1), Some(&1));
+/// assert_eq!(v.get(3), Some(&2));
+/// assert!(v.is_hole(4));
+/// assert_eq!(v.next_index(), 0);
+///
+/// assert_eq!(v.push(3), 0);
+/// assert_eq!(v.push(4), 2);
+/// assert_eq!(v.push(5), 4);
+/// assert_eq!(v.push(6), 5);
+/// ```
+impl<T> From<Vec<Option<T>>> for HoleyVec<T> {
+    fn from(vec: Vec<Option<T>>) -> Self {
+        let mut len = 0;
+        let vec: Vec<Cell<T, usize>> = vec.into_iter()
+            .map(|value| match value {
+                Some(value) => {
+                    len += 1;
+                    Cell::Value(value)
+                },
+                None => Cell::Hole(0),
+            })
+            .collect();
+        let mut result = Self { len, vec, ..Self::default() };
+        result.link_holes();
+        result.fill_occupied();
+        result
     }
 }
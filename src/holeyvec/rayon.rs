@@ -0,0 +1,87 @@
+//! [rayon] support for [HoleyVec], enabled by `rayon` feature.
+//!
+//! Parallel iterators split the underlying vector by ranges of indexes, not by the number of
+//! non-empty elements. Thus the work is balanced well only when holes are spread evenly, a range
+//! full of holes gives its thread nothing to do.
+//!
+//! ## Examples
+//! ```
+//! use holeyvec::HoleyVec;
+//! use rayon::prelude::*;
+//!
+//! let mut v: HoleyVec<u64> = (0..100_000).collect();
+//! for index in (0..100_000).step_by(3) {
+//!     v.remove(index);
+//! }
+//! assert_eq!(v.par_iter().sum::<u64>(), v.iter().sum::<u64>());
+//!
+//! v.par_iter_mut().for_each(|value| *value *= 2);
+//! assert_eq!(v.par_iter().count(), v.len());
+//! assert_eq!(v.get(1), Some(&2));
+//! assert_eq!(v.get(3), None);
+//! ```
+
+use rayon::iter::plumbing::UnindexedConsumer;
+use rayon::iter::FilterMap;
+use rayon::prelude::*;
+
+use super::{Cell, HoleyVec, Index};
+
+impl<T, Idx: Index> HoleyVec<T, Idx> {
+    /// Return parallel iterator over non-empty elements of the vector.
+    pub fn par_iter(&self) -> ParIter<'_, T, Idx> where T: Sync, Idx: Sync {
+        ParIter{ delegate: self.vec.par_iter().filter_map(value) }
+    }
+
+    /// Return parallel iterator over non-empty elements of the vector which allows modifying
+    /// the values.
+    pub fn par_iter_mut(&mut self) -> ParIterMut<'_, T, Idx> where T: Send, Idx: Send {
+        ParIterMut{ delegate: self.vec.par_iter_mut().filter_map(value_mut) }
+    }
+}
+
+fn value<T, Idx>(cell: &Cell<T, Idx>) -> Option<&T> {
+    match cell {
+        Cell::Value(value) => Some(value),
+        Cell::Hole(_) => None,
+    }
+}
+
+fn value_mut<T, Idx>(cell: &mut Cell<T, Idx>) -> Option<&mut T> {
+    match cell {
+        Cell::Value(value) => Some(value),
+        Cell::Hole(_) => None,
+    }
+}
+
+/// Function extracting value from the element.
+type Value<'a, T, Idx> = fn(&'a Cell<T, Idx>) -> Option<&'a T>;
+
+/// [HoleyVec] parallel iterator over non-empty elements, see [HoleyVec::par_iter].
+pub struct ParIter<'a, T, Idx = usize> {
+    delegate: FilterMap<rayon::slice::Iter<'a, Cell<T, Idx>>, Value<'a, T, Idx>>,
+}
+
+impl<'a, T: Sync, Idx: Index + Sync> ParallelIterator for ParIter<'a, T, Idx> {
+    type Item = &'a T;
+
+    fn drive_unindexed<C: UnindexedConsumer<Self::Item>>(self, consumer: C) -> C::Result {
+        self.delegate.drive_unindexed(consumer)
+    }
+}
+
+/// Function extracting mutable value from the element.
+type ValueMut<'a, T, Idx> = fn(&'a mut Cell<T, Idx>) -> Option<&'a mut T>;
+
+/// [HoleyVec] parallel iterator over non-empty elements, see [HoleyVec::par_iter_mut].
+pub struct ParIterMut<'a, T, Idx = usize> {
+    delegate: FilterMap<rayon::slice::IterMut<'a, Cell<T, Idx>>, ValueMut<'a, T, Idx>>,
+}
+
+impl<'a, T: Send, Idx: Index + Send> ParallelIterator for ParIterMut<'a, T, Idx> {
+    type Item = &'a mut T;
+
+    fn drive_unindexed<C: UnindexedConsumer<Self::Item>>(self, consumer: C) -> C::Result {
+        self.delegate.drive_unindexed(consumer)
+    }
+}
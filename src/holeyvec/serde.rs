@@ -0,0 +1,296 @@
+//! [serde] support for [HoleyVec], enabled by `serde` feature.
+//!
+//! Vector is serialized as a structure which keeps the number of indexes used, the indexes of
+//! holes in the order they are going to be reused by [HoleyVec::push], the map of indexes to
+//! non-empty values and the order of reusing holes (see [Reuse]). Thus the deserialized vector
+//! has the same values and reuses holes in the same order as the serialized one. The maximal
+//! index set by [HoleyVec::with_max_index] and generations of elements are not preserved, keys
+//! (see [Key](crate::Key)) issued before serialization must not be used with the deserialized vector.
+//! Missing `reuse` field is deserialized as [Reuse::LastRemoved].
+//!
+//! ## Examples
+//! ```
+//! use holeyvec::{HoleyVec, Reuse};
+//!
+//! let mut v: HoleyVec<i32> = (0..6).collect();
+//! v.remove(1);
+//! v.remove(4);
+//! v.remove(5);
+//!
+//! let json = serde_json::to_string(&v).unwrap();
+//! assert_eq!(json, r#"{"index_upper_bound":6,"holes":[5,4,1],"values":{"0":0,"2":2,"3":3},"reuse":"LastRemoved"}"#);
+//!
+//! let mut w: HoleyVec<i32> = serde_json::from_str(&json).unwrap();
+//! assert_eq!(w, v);
+//! assert_eq!(w.index_upper_bound(), 6);
+//! assert_eq!(w.next_index(), v.next_index());
+//! assert_eq!(w.push(6), 5);
+//! assert_eq!(w.push(7), 4);
+//! assert_eq!(w.push(8), 1);
+//! assert_eq!(w.push(9), 6);
+//!
+//! assert!(serde_json::from_str::<HoleyVec<i32>>(
+//!     r#"{"index_upper_bound":2,"holes":[],"values":{"0":0}}"#).is_err());
+//!
+//! let mut v: HoleyVec<i32> = HoleyVec::with_reuse(Reuse::LowestIndex);
+//! v.extend(0..4);
+//! v.remove(2);
+//! v.remove(0);
+//! let mut w: HoleyVec<i32> = serde_json::from_str(&serde_json::to_string(&v).unwrap()).unwrap();
+//! assert_eq!(w.reuse(), Reuse::LowestIndex);
+//! w.remove(1);
+//! assert_eq!(w.holes().collect::<Vec<usize>>(), vec![0, 1, 2]);
+//!
+//! let w: HoleyVec<i32> = serde_json::from_str(
+//!     r#"{"index_upper_bound":2,"holes":[1],"values":{"0":0}}"#).unwrap();
+//! assert_eq!(w.reuse(), Reuse::LastRemoved);
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use super::{to_link, Cell, HoleyVec, Index, Reuse, NO_HOLE};
+
+const FIELDS: &[&str] = &["index_upper_bound", "holes", "values", "reuse"];
+
+const REUSE_VARIANTS: &[&str] = &["LastRemoved", "LowestIndex"];
+
+impl<T: Serialize, Idx: Index> Serialize for HoleyVec<T, Idx> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("HoleyVec", FIELDS.len())?;
+        state.serialize_field("index_upper_bound", &self.vec.len())?;
+        state.serialize_field("holes", &Holes(self))?;
+        state.serialize_field("values", &Values(self))?;
+        state.serialize_field("reuse", &self.reuse)?;
+        state.end()
+    }
+}
+
+/// Indexes of holes in the order of reuse.
+struct Holes<'a, T, Idx>(&'a HoleyVec<T, Idx>);
+
+impl<'a, T, Idx: Index> Serialize for Holes<'a, T, Idx> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.holes())
+    }
+}
+
+/// Map of indexes to non-empty values.
+struct Values<'a, T, Idx>(&'a HoleyVec<T, Idx>);
+
+impl<'a, T: Serialize, Idx: Index> Serialize for Values<'a, T, Idx> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter_indexed())
+    }
+}
+
+/// Reuse order is serialized as a name of the variant.
+impl Serialize for Reuse {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match self {
+            Reuse::LastRemoved => "LastRemoved",
+            Reuse::LowestIndex => "LowestIndex",
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Reuse {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_identifier(ReuseVisitor)
+    }
+}
+
+struct ReuseVisitor;
+
+impl<'de> Visitor<'de> for ReuseVisitor {
+    type Value = Reuse;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("`LastRemoved` or `LowestIndex`")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Reuse, E> {
+        match value {
+            "LastRemoved" => Ok(Reuse::LastRemoved),
+            "LowestIndex" => Ok(Reuse::LowestIndex),
+            _ => Err(de::Error::unknown_variant(value, REUSE_VARIANTS)),
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>, Idx: Index> Deserialize<'de> for HoleyVec<T, Idx> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("HoleyVec", FIELDS, HoleyVecVisitor(PhantomData))
+    }
+}
+
+enum Field {
+    IndexUpperBound,
+    Holes,
+    Values,
+    Reuse,
+}
+
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_identifier(FieldVisitor)
+    }
+}
+
+struct FieldVisitor;
+
+impl<'de> Visitor<'de> for FieldVisitor {
+    type Value = Field;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("`index_upper_bound`, `holes`, `values` or `reuse`")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Field, E> {
+        match value {
+            "index_upper_bound" => Ok(Field::IndexUpperBound),
+            "holes" => Ok(Field::Holes),
+            "values" => Ok(Field::Values),
+            "reuse" => Ok(Field::Reuse),
+            _ => Err(de::Error::unknown_field(value, FIELDS)),
+        }
+    }
+}
+
+/// Deserialized map of indexes to non-empty values.
+struct Entries<T>(Vec<(usize, T)>);
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Entries<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(EntriesVisitor(PhantomData))
+    }
+}
+
+struct EntriesVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for EntriesVisitor<T> {
+    type Value = Entries<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a map of indexes to values")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some(entry) = map.next_entry()? {
+            entries.push(entry);
+        }
+        Ok(Entries(entries))
+    }
+}
+
+struct HoleyVecVisitor<T, Idx>(PhantomData<(T, Idx)>);
+
+impl<'de, T: Deserialize<'de>, Idx: Index> Visitor<'de> for HoleyVecVisitor<T, Idx> {
+    type Value = HoleyVec<T, Idx>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("struct HoleyVec")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let upper_bound = seq.next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let holes = seq.next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        let Entries(values) = seq.next_element()?
+            .ok_or_else(|| de::Error::invalid_length(2, &self))?;
+        let reuse = seq.next_element()?.unwrap_or_default();
+        build(upper_bound, holes, values, reuse)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut upper_bound = None;
+        let mut holes = None;
+        let mut values = None;
+        let mut reuse = None;
+        while let Some(key) = map.next_key()? {
+            match key {
+                Field::IndexUpperBound => {
+                    if upper_bound.is_some() {
+                        return Err(de::Error::duplicate_field("index_upper_bound"));
+                    }
+                    upper_bound = Some(map.next_value()?);
+                },
+                Field::Holes => {
+                    if holes.is_some() {
+                        return Err(de::Error::duplicate_field("holes"));
+                    }
+                    holes = Some(map.next_value()?);
+                },
+                Field::Values => {
+                    if values.is_some() {
+                        return Err(de::Error::duplicate_field("values"));
+                    }
+                    let Entries(entries) = map.next_value()?;
+                    values = Some(entries);
+                },
+                Field::Reuse => {
+                    if reuse.is_some() {
+                        return Err(de::Error::duplicate_field("reuse"));
+                    }
+                    reuse = Some(map.next_value()?);
+                },
+            }
+        }
+        let upper_bound = upper_bound
+            .ok_or_else(|| de::Error::missing_field("index_upper_bound"))?;
+        let holes = holes.ok_or_else(|| de::Error::missing_field("holes"))?;
+        let values = values.ok_or_else(|| de::Error::missing_field("values"))?;
+        build(upper_bound, holes, values, reuse.unwrap_or_default())
+    }
+}
+
+/// Restore vector checking that values and holes together cover each index exactly once. Holes
+/// are sorted when they are reused starting from the lowest index.
+fn build<T, Idx: Index, E: de::Error>(upper_bound: usize, holes: Vec<usize>,
+    values: Vec<(usize, T)>, reuse: Reuse) -> Result<HoleyVec<T, Idx>, E> {
+    if upper_bound > Idx::MAX.into_usize() {
+        return Err(E::custom(format_args!("index upper bound {} exceeds the maximal index {:?}",
+            upper_bound, Idx::MAX)));
+    }
+    if holes.len() + values.len() != upper_bound {
+        return Err(E::custom(format_args!("expected {} holes and values in total, found {}",
+            upper_bound, holes.len() + values.len())));
+    }
+    let len = values.len();
+    let mut cells: Vec<Option<T>> = core::iter::repeat_with(|| None).take(upper_bound).collect();
+    for (index, value) in values {
+        match cells.get_mut(index) {
+            Some(cell @ None) => *cell = Some(value),
+            Some(Some(_)) => return Err(E::custom(format_args!("duplicate value index {}", index))),
+            None => return Err(E::custom(format_args!("value index {} is out of bounds", index))),
+        }
+    }
+    let mut vec: Vec<Cell<T, Idx>> = cells.into_iter()
+        .map(|cell| match cell {
+            Some(value) => Cell::Value(value),
+            None => Cell::Hole(Idx::MAX),
+        })
+        .collect();
+    let mut linked = alloc::vec![false; upper_bound];
+    let mut next_hole = NO_HOLE;
+    for &index in holes.iter().rev() {
+        match vec.get_mut(index) {
+            Some(Cell::Hole(next)) if !linked[index] => {
+                linked[index] = true;
+                *next = to_link(next_hole);
+                next_hole = index;
+            },
+            _ => return Err(E::custom(format_args!("invalid hole index {}", index))),
+        }
+    }
+    let mut result = HoleyVec{ first_hole: next_hole, len, vec, ..HoleyVec::default() };
+    result.fill_occupied();
+    result.set_reuse(reuse);
+    Ok(result)
+}
@@ -1,6 +1,9 @@
 #![doc = include_str!("../README.md")]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 mod holeyvec;
 
 pub use holeyvec::*;
-
+pub use alloc::collections::TryReserveError;
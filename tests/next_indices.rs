@@ -0,0 +1,19 @@
+//! Checks that `next_indices()` previews exactly the indexes taken by the following pushes.
+
+use holeyvec::HoleyVec;
+
+#[test]
+fn next_indices_match_pushes() {
+    let mut v: HoleyVec<usize> = HoleyVec::new();
+    for step in 0..200 {
+        if step % 3 == 0 {
+            v.insert(step * 7 % 50, step);
+        } else if let Some((index, _)) = v.iter_indexed().nth(step * 5 % (v.len() + 1)) {
+            v.remove(index);
+        }
+        let n = step % 10;
+        let mut w = v.clone();
+        let pushed: Vec<usize> = (0..n).map(|value| w.push(value)).collect();
+        assert_eq!(v.next_indices(n), pushed);
+    }
+}
@@ -0,0 +1,21 @@
+//! Checks that the crate is usable from a `no_std` crate which has only `alloc`. Run it with
+//! `cargo test --no-default-features --test no_std`.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+use holeyvec::HoleyVec;
+
+#[test]
+fn holeyvec_without_std() {
+    let mut v: HoleyVec<u32> = (0..4).collect();
+    assert_eq!(v.remove(1), 1);
+    assert_eq!(v[2], 2);
+    v[3] = 30;
+    assert_eq!(v.push(4), 1);
+    assert_eq!(v.iter().copied().collect::<Vec<u32>>(), [0, 4, 2, 30]);
+    assert_eq!(BTreeMap::from(v).into_iter().collect::<Vec<_>>(), [(0, 0), (1, 4), (2, 2), (3, 30)]);
+}
@@ -0,0 +1,56 @@
+//! Checks that the occupancy bitset stays consistent with the values of the vector.
+
+use holeyvec::{HoleyVec, Reuse};
+
+#[test]
+fn reserve_holes_keeps_values() {
+    let mut v: HoleyVec<i32> = (0..40).collect();
+    v.reserve_holes(100);
+    v.truncate(70);
+    assert_eq!(v.iter().count(), v.len());
+    assert_eq!(v.last(), Some((39, &39)));
+
+    v.reserve_holes(200);
+    v.shrink_to(100);
+    assert_eq!(v.iter().count(), v.len());
+
+    v.reserve_holes(130);
+    v.resize_with(110, || unreachable!());
+    assert_eq!(v.iter().count(), v.len());
+
+    v.reserve_holes(150);
+    let w = v.split_off(120);
+    assert!(w.is_empty());
+    assert_eq!(v.iter().count(), v.len());
+    assert_eq!(v.iter().copied().collect::<Vec<i32>>(), (0..40).collect::<Vec<i32>>());
+}
+
+#[test]
+fn reserve_holes_keeps_sorted_holes() {
+    let mut v: HoleyVec<i32> = HoleyVec::with_reuse(Reuse::LowestIndex);
+    v.extend(0..40);
+    v.reserve_holes(100);
+    v.truncate(70);
+    v.remove(5);
+    assert_eq!(v.iter().count(), v.len());
+    assert!(v.holes().eq(std::iter::once(5).chain(40..70)));
+}
+
+#[test]
+fn iterators_skip_holes_at_word_boundaries() {
+    let sparse = [0, 63, 64, 65, 127, 4096, 999_999];
+    let mut v = HoleyVec::new();
+    for &index in sparse.iter() {
+        v.insert(index, index);
+    }
+    v.insert(1_000_000, 0);
+    v.remove(1_000_000);
+    assert_eq!(v.indices().collect::<Vec<_>>(), sparse);
+    assert_eq!(v.indices().rev().collect::<Vec<_>>(), sparse.iter().rev().copied().collect::<Vec<_>>());
+    assert!(v.iter_indexed().all(|(index, value)| index == *value));
+
+    v.retain(|index, _| index % 64 != 63);
+    assert_eq!(v.iter().copied().collect::<Vec<_>>(), vec![0, 64, 65, 4096]);
+    v.truncate(65);
+    assert_eq!(v.values_mut().map(|value| *value).collect::<Vec<_>>(), vec![0, 64]);
+}